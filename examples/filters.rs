@@ -263,6 +263,16 @@ fn main() {
         InputSource::Unit(input.clone()),
         wav_cfg()
     );
+    // Allpass looks flat in the time domain, its phase and group delay tell the story
+    assert_audio_unit_snapshot!(
+        "filter_allpass_1k_q0_707",
+        allpass_hz(1_000.0, 0.707),
+        InputSource::Unit(input.clone()),
+        SnapshotConfigBuilder::default()
+            .output_mode(GroupDelayConfig::default())
+            .build()
+            .unwrap()
+    );
 
     // Butterworth lowpass 1 kHz (2nd order)
     assert_audio_unit_snapshot!(
@@ -2,10 +2,14 @@ use plotters::{coord::types::RangedCoordf64, prelude::*};
 
 use crate::chart_data::ChannelChartData;
 
+/// Kind of abnormal sample produced by an audio unit
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum AbnormalSample {
+pub enum AbnormalSample {
+    /// `NaN`
     Nan,
+    /// `-Infinity`
     NegInf,
+    /// `+Infinity`
     PosInf,
 }
 
@@ -33,9 +37,10 @@ impl From<f32> for AbnormalSample {
     }
 }
 
-pub(crate) fn abnormal_smaples_series(
+pub(crate) fn abnormal_smaples_series<DB: DrawingBackend>(
     charts_data: &[ChannelChartData],
-    chart: &mut ChartContext<'_, SVGBackend<'_>, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    start_from: usize,
     y_min: f64,
     y_max: f64,
 ) {
@@ -47,7 +52,7 @@ pub(crate) fn abnormal_smaples_series(
                     .flat_map(|d| d.abnormalities.iter())
                     .map(|&(i, ab)| {
                         (
-                            i as f64,
+                            (i + start_from) as f64,
                             match ab {
                                 AbnormalSample::Nan => 0.0,
                                 AbnormalSample::NegInf => y_min.min(-1.0),
@@ -0,0 +1,343 @@
+use plotters::backend::SVGBackend;
+use plotters::drawing::IntoDrawingArea;
+use plotters::element::DashedPathElement;
+use plotters::prelude::*;
+
+use crate::chart::{finish_svg, group_axis_color, layout_groups};
+use crate::chart_data::ChannelChartData;
+use crate::config::{AliasingConfig, SvgChartConfig};
+use crate::error::SnapshotError;
+use crate::harmonics::{LobeSpectrum, main_lobe_half_width, relative_db};
+use crate::spectrum::{WindowFunction, draw_legend, fft_size};
+use crate::util::{frequency_formatter, get_contrasting_color, parse_hex_color};
+
+const DEFAULT_WIDTH: usize = 1000;
+/// Highest swept fundamental relative to Nyquist,
+/// keeps the fundamental lobe clear of Nyquist where no harmonic is left to measure against
+const MAX_FUNDAMENTAL_RATIO: f64 = 0.9;
+
+/// Aliasing of one output at one swept fundamental frequency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AliasMeasurement {
+    /// Swept fundamental frequency in Hz
+    pub frequency: f64,
+    /// Level of the fundamental in dBFS
+    pub fundamental_level: f64,
+    /// Power of all non-harmonic components relative to the fundamental in dBc
+    pub alias_level: f64,
+    /// Level of the strongest non-harmonic component relative to the fundamental in dBc
+    pub max_alias_level: f64,
+    /// Frequency of the strongest non-harmonic component in Hz
+    pub max_alias_frequency: f64,
+}
+
+impl AliasMeasurement {
+    /// Measure aliasing of `samples` with fundamental at `frequency`
+    ///
+    /// Everything outside the main lobes of DC, of the fundamental and of harmonics below Nyquist
+    /// counts as aliasing, including noise and window leakage, which sets a floor around -85 dBc
+    /// with `WindowFunction::BlackmanHarris`.
+    /// Fundamentals below [min_resolvable_frequency] leave few or no bins between harmonic lobes.
+    pub fn measure(
+        samples: &[f32],
+        frequency: f64,
+        sample_rate: f64,
+        config: &AliasingConfig,
+    ) -> Self {
+        let spectrum = LobeSpectrum::new(samples, sample_rate, config.window);
+        let fundamental_bin = spectrum.peak_near(frequency);
+        let fundamental_power = spectrum.lobe_power(fundamental_bin);
+
+        // Harmonic lobes one bin wider, harmonics fall between bins.
+        // At most one harmonic per bin, lobes of closer harmonics cover the whole spectrum
+        let nyquist = sample_rate / 2.0;
+        let mut harmonic_bins = vec![false; spectrum.last_bin() + 1];
+        let harmonic_lobes = (1..=spectrum.last_bin())
+            .map(|number| frequency * number as f64)
+            .take_while(|&harmonic| harmonic < nyquist)
+            .map(|harmonic| spectrum.lobe(spectrum.bin(harmonic)))
+            .chain(std::iter::once(spectrum.lobe(fundamental_bin)));
+        for lobe in harmonic_lobes {
+            let widened =
+                lobe.start().saturating_sub(1)..=(lobe.end() + 1).min(spectrum.last_bin());
+            harmonic_bins[widened].fill(true);
+        }
+        let is_harmonic = |bin: usize| spectrum.is_dc(bin) || harmonic_bins[bin];
+
+        let alias_power = spectrum.power_excluding(is_harmonic);
+        // Strongest component with its leakage into neighbouring harmonic lobes left out
+        let (max_alias_level, max_alias_frequency) = spectrum
+            .strongest_excluding(is_harmonic)
+            .map_or((f64::NEG_INFINITY, 0.0), |bin| {
+                (
+                    relative_db(
+                        spectrum.lobe_power_excluding(bin, is_harmonic),
+                        fundamental_power,
+                    ),
+                    spectrum.peak_frequency(bin),
+                )
+            });
+
+        Self {
+            frequency,
+            fundamental_level: spectrum.level(fundamental_power),
+            alias_level: relative_db(alias_power, fundamental_power),
+            max_alias_level,
+            max_alias_frequency,
+        }
+    }
+}
+
+/// Aliasing of every output across swept fundamental frequencies
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let config = AliasingConfigBuilder::default()
+///     .min_frequency(1000.0)
+///     .max_frequency(8000.0)
+///     .num_frequencies(4)
+///     .build()
+///     .unwrap();
+/// let sweep = measure_aliasing(|frequency| saw_hz(frequency), &config);
+/// assert_eq!(sweep.outputs[0].len(), 4);
+/// assert!(sweep.worst().unwrap().alias_level < -60.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasingSweep {
+    /// Measurements of every output channel, ordered by frequency
+    pub outputs: Vec<Vec<AliasMeasurement>>,
+}
+
+impl AliasingSweep {
+    /// Measurement with the highest `alias_level` of all outputs
+    pub fn worst(&self) -> Option<&AliasMeasurement> {
+        self.outputs
+            .iter()
+            .flatten()
+            .max_by(|a, b| a.alias_level.total_cmp(&b.alias_level))
+    }
+
+    /// Check `alias_level` of every measurement against `limit` in dBc
+    ///
+    /// Returns the first exceeding measurement as [SnapshotError::AliasingLimitExceeded]
+    pub fn check(&self, limit: f64) -> Result<(), SnapshotError> {
+        for (channel, measurements) in self.outputs.iter().enumerate() {
+            if let Some(measurement) = measurements.iter().find(|m| m.alias_level > limit) {
+                return Err(SnapshotError::AliasingLimitExceeded {
+                    channel,
+                    frequency: measurement.frequency,
+                    level: measurement.alias_level,
+                    limit,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Render alias level versus fundamental frequency of every output as SVG,
+    /// with `max_alias_level` of the config as a reference line
+    pub fn render_svg(&self, config: &AliasingConfig) -> Vec<u8> {
+        generate_aliasing_svg(self, config).into_bytes()
+    }
+}
+
+/// Lowest fundamental whose harmonic lobes leave at least as many bins between them as they cover
+///
+/// Below it alias components can't be told from harmonics, down to no alias bins at all
+pub fn min_resolvable_frequency(
+    window: WindowFunction,
+    num_samples: usize,
+    sample_rate: f64,
+) -> f64 {
+    let frame_size = num_samples.min(fft_size(None, num_samples)).max(1);
+    (4 * (main_lobe_half_width(window) + 1)) as f64 * sample_rate / frame_size as f64
+}
+
+/// Logarithmically spaced fundamental frequencies swept by `config`,
+/// from [min_resolvable_frequency] up to [MAX_FUNDAMENTAL_RATIO] of Nyquist at most
+pub(crate) fn sweep_frequencies(config: &AliasingConfig) -> Vec<f64> {
+    let max_frequency = config
+        .max_frequency
+        .min(config.sample_rate / 2.0 * MAX_FUNDAMENTAL_RATIO);
+    let min_frequency = config
+        .min_frequency
+        .max(min_resolvable_frequency(
+            config.window,
+            config.num_samples,
+            config.sample_rate,
+        ))
+        .min(max_frequency);
+    let steps = config.num_frequencies.saturating_sub(1).max(1) as f64;
+    (0..config.num_frequencies)
+        .map(|i| min_frequency * (max_frequency / min_frequency).powf(i as f64 / steps))
+        .collect()
+}
+
+fn generate_aliasing_svg(sweep: &AliasingSweep, config: &AliasingConfig) -> String {
+    let chart_config = &config.chart;
+    let output_charts: Vec<ChannelChartData> = sweep
+        .outputs
+        .iter()
+        .enumerate()
+        .map(|(i, measurements)| {
+            let levels: Vec<f32> = measurements
+                .iter()
+                .map(|m| m.alias_level.max(config.min_db) as f32)
+                .collect();
+            ChannelChartData::from_output_data(&levels, &[], i, chart_config)
+        })
+        .collect();
+    let groups = layout_groups(chart_config, vec![], output_charts);
+    let frequencies: Vec<f64> = sweep
+        .outputs
+        .first()
+        .map(|measurements| measurements.iter().map(|m| m.frequency).collect())
+        .unwrap_or_default();
+
+    if groups.is_empty() || frequencies.is_empty() {
+        return "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\"><text>Empty</text></svg>".to_string();
+    }
+
+    let svg_width = chart_config.svg_width.unwrap_or(DEFAULT_WIDTH) as u32;
+    let total_height = (chart_config.svg_height_per_channel * groups.len()) as u32;
+
+    let mut svg_buffer = String::new();
+    {
+        let root =
+            SVGBackend::with_string(&mut svg_buffer, (svg_width, total_height)).into_drawing_area();
+
+        let bg_color = parse_hex_color(&chart_config.background_color);
+        root.fill(&bg_color).unwrap();
+
+        let current_area = if let Some(ref title) = chart_config.chart_title {
+            let title_color = get_contrasting_color(&bg_color);
+            let text_style = TextStyle::from(("sans-serif", 20)).color(&title_color);
+            root.titled(title, text_style).unwrap()
+        } else {
+            root
+        };
+
+        let areas = current_area.split_evenly((groups.len(), 1));
+        for (group, area) in groups.iter().zip(areas.iter()) {
+            aliasing_chart(group, &frequencies, config, area);
+        }
+
+        current_area.present().unwrap();
+    }
+
+    finish_svg(svg_buffer, chart_config, svg_width, total_height)
+}
+
+/// Alias level of `group` channels on a logarithmic frequency axis, with markers at measurements
+fn aliasing_chart<DB: DrawingBackend>(
+    group: &[ChannelChartData],
+    frequencies: &[f64],
+    config: &AliasingConfig,
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+) {
+    let chart_config: &SvgChartConfig = &config.chart;
+    let axis_color = group_axis_color(group);
+
+    let min_frequency = frequencies.first().copied().unwrap_or(1.0);
+    let max_frequency = frequencies
+        .last()
+        .copied()
+        .unwrap_or(min_frequency)
+        .max(min_frequency * 2.0);
+    let max_db = group
+        .iter()
+        .flat_map(|entry| entry.data.iter().map(|&db| db as f64))
+        .chain(config.max_alias_level)
+        .fold(config.min_db, f64::max);
+    let y_max = ((max_db / 10.0).floor() * 10.0 + 10.0).max(config.min_db + 10.0);
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(5)
+        .x_label_area_size(if chart_config.show_labels { 35 } else { 0 })
+        .y_label_area_size(if chart_config.show_labels { 50 } else { 0 })
+        .build_cartesian_2d(
+            (min_frequency..max_frequency).log_scale(),
+            config.min_db..y_max,
+        )
+        .unwrap();
+
+    let mut mesh = chart.configure_mesh();
+    mesh.axis_style(axis_color.mix(0.3));
+    if !chart_config.show_grid {
+        mesh.disable_mesh();
+    } else {
+        mesh.light_line_style(axis_color.mix(0.1))
+            .bold_line_style(axis_color.mix(0.2));
+    }
+    let x_formatter = |v: &f64| frequency_formatter(*v);
+    let y_formatter = |v: &f64| format!("{v:.0}");
+    if chart_config.show_labels {
+        mesh.x_labels(chart_config.max_labels_x_axis.unwrap_or(10))
+            .y_labels(5)
+            .x_label_formatter(&x_formatter)
+            .y_label_formatter(&y_formatter)
+            .x_desc("Fundamental (Hz)")
+            .y_desc("Alias level (dBc)")
+            .label_style(("sans-serif", 10, &axis_color));
+    }
+    mesh.draw().unwrap();
+
+    let mut has_legend = false;
+    if let Some(limit) = config.max_alias_level {
+        let color = get_contrasting_color(&parse_hex_color(&chart_config.background_color));
+        chart
+            .draw_series(std::iter::once(DashedPathElement::new(
+                [(min_frequency, limit), (max_frequency, limit)],
+                6,
+                4,
+                color.mix(0.6),
+            )))
+            .unwrap()
+            .label(format!("Limit {limit:.1} dBc"))
+            .legend(move |(x, y)| {
+                DashedPathElement::new(vec![(x, y), (x + 20, y)], 6, 4, color.mix(0.6))
+            });
+        has_legend = true;
+    }
+
+    for entry in group {
+        let color = entry.color;
+        let line_style = ShapeStyle {
+            color: color.to_rgba(),
+            filled: false,
+            stroke_width: chart_config.line_width as u32,
+        };
+        let points: Vec<(f64, f64)> = frequencies
+            .iter()
+            .zip(&entry.data)
+            .map(|(&frequency, &db)| (frequency, db as f64))
+            .collect();
+
+        chart
+            .draw_series(
+                points
+                    .iter()
+                    .map(|&point| Circle::new(point, 3, color.filled())),
+            )
+            .unwrap();
+        let series = chart
+            .draw_series(std::iter::once(PathElement::new(points, line_style)))
+            .unwrap();
+        if group.len() > 1 || config.max_alias_level.is_some() {
+            let label = entry
+                .label
+                .clone()
+                .unwrap_or_else(|| "Alias level".to_string());
+            series
+                .label(label)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+            has_legend = true;
+        }
+    }
+
+    if has_legend {
+        draw_legend(&mut chart, chart_config);
+    }
+}
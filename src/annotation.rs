@@ -0,0 +1,208 @@
+use plotters::{coord::types::RangedCoordf64, prelude::*};
+
+use crate::config::SvgChartConfig;
+use crate::util::{get_contrasting_color, parse_hex_color};
+
+/// Position along the X axis of waveform charts
+///
+/// Counts from the start of processing, same as X axis labels, so warm-up samples are included
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartPosition {
+    /// Sample index
+    Sample(usize),
+    /// Time in seconds
+    Time(f64),
+}
+
+impl ChartPosition {
+    fn x(&self, sample_rate: f64) -> f64 {
+        match *self {
+            ChartPosition::Sample(sample) => sample as f64,
+            ChartPosition::Time(seconds) => seconds * sample_rate,
+        }
+    }
+}
+
+impl From<usize> for ChartPosition {
+    fn from(sample: usize) -> Self {
+        ChartPosition::Sample(sample)
+    }
+}
+
+impl From<f64> for ChartPosition {
+    fn from(seconds: f64) -> Self {
+        ChartPosition::Time(seconds)
+    }
+}
+
+/// Kind of annotation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnnotationKind {
+    /// Vertical line at a position, e.g. gate on or a parameter change
+    Marker(ChartPosition),
+    /// Shaded region between two positions
+    Region(ChartPosition, ChartPosition),
+    /// Horizontal reference line at a sample value
+    Level(f32),
+}
+
+/// Event or reference drawn on every waveform chart
+///
+/// ```
+/// use insta_fun::prelude::*;
+///
+/// let chart = SvgChartConfigBuilder::default()
+///     .annotation(Annotation::marker(0.1).label("gate on"))
+///     .annotation(Annotation::region(100, 200).label("release").color("#FF6D00"))
+///     .annotation(Annotation::level_db(-3.0).label("-3 dB"))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// What to draw and where
+    pub kind: AnnotationKind,
+    /// Optional label next to the annotation
+    pub label: Option<String>,
+    /// Color (hex string), `None` contrasts with the chart background
+    pub color: Option<String>,
+}
+
+impl Annotation {
+    /// Annotation of `kind` without label, in the default color
+    pub fn new(kind: AnnotationKind) -> Self {
+        Self {
+            kind,
+            label: None,
+            color: None,
+        }
+    }
+
+    /// Vertical marker at a sample index (`usize`) or time in seconds (`f64`)
+    pub fn marker(at: impl Into<ChartPosition>) -> Self {
+        Self::new(AnnotationKind::Marker(at.into()))
+    }
+
+    /// Shaded region between sample indices (`usize`) or times in seconds (`f64`)
+    pub fn region(from: impl Into<ChartPosition>, to: impl Into<ChartPosition>) -> Self {
+        Self::new(AnnotationKind::Region(from.into(), to.into()))
+    }
+
+    /// Horizontal reference line at sample `value`
+    pub fn level(value: f32) -> Self {
+        Self::new(AnnotationKind::Level(value))
+    }
+
+    /// Horizontal reference line at amplitude of `db` dBFS
+    pub fn level_db(db: f32) -> Self {
+        Self::level(10.0_f32.powf(db / 20.0))
+    }
+
+    /// Set label
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set color (hex string)
+    pub fn color(mut self, color: impl Into<String>) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+}
+
+fn annotation_color(annotation: &Annotation, config: &SvgChartConfig) -> RGBColor {
+    match &annotation.color {
+        Some(color) => parse_hex_color(color),
+        None => get_contrasting_color(&parse_hex_color(&config.background_color)),
+    }
+}
+
+/// Draw shaded regions, before waveforms so waveforms stay visible on top
+pub(crate) fn draw_regions<DB: DrawingBackend>(
+    chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    config: &SvgChartConfig,
+    sample_rate: f64,
+) {
+    let (x_range, y_range) = (chart.x_range(), chart.y_range());
+    for annotation in &config.annotations {
+        let AnnotationKind::Region(from, to) = annotation.kind else {
+            continue;
+        };
+        let (from, to) = (from.x(sample_rate), to.x(sample_rate));
+        let (from, to) = (
+            from.min(to).max(x_range.start),
+            from.max(to).min(x_range.end),
+        );
+        if from >= to {
+            continue;
+        }
+
+        let color = annotation_color(annotation, config);
+        chart
+            .draw_series(std::iter::once(Rectangle::new(
+                [(from, y_range.start), (to, y_range.end)],
+                color.mix(0.15).filled(),
+            )))
+            .unwrap();
+        // Bottom left, away from marker labels
+        draw_label(chart, annotation, color, (from, y_range.start), (4, -12));
+    }
+}
+
+/// Draw markers and reference levels over waveforms
+pub(crate) fn draw_markers<DB: DrawingBackend>(
+    chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    config: &SvgChartConfig,
+    sample_rate: f64,
+) {
+    let (x_range, y_range) = (chart.x_range(), chart.y_range());
+    for annotation in &config.annotations {
+        let color = annotation_color(annotation, config);
+        let (line, label_at) = match annotation.kind {
+            AnnotationKind::Marker(at) => {
+                let x = at.x(sample_rate);
+                if !x_range.contains(&x) {
+                    continue;
+                }
+                ([(x, y_range.start), (x, y_range.end)], (x, y_range.end))
+            }
+            AnnotationKind::Level(value) => {
+                let y = config.y_scale.apply(value) as f64;
+                if !y_range.contains(&y) {
+                    continue;
+                }
+                ([(x_range.start, y), (x_range.end, y)], (x_range.start, y))
+            }
+            AnnotationKind::Region(..) => continue,
+        };
+        chart
+            .draw_series(std::iter::once(PathElement::new(
+                line,
+                color.mix(0.8).stroke_width(1),
+            )))
+            .unwrap();
+        draw_label(chart, annotation, color, label_at, (4, 2));
+    }
+}
+
+fn draw_label<DB: DrawingBackend>(
+    chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    annotation: &Annotation,
+    color: RGBColor,
+    at: (f64, f64),
+    offset: (i32, i32),
+) {
+    if let Some(label) = &annotation.label {
+        chart
+            .draw_series(std::iter::once(
+                EmptyElement::at(at)
+                    + Text::new(
+                        label.clone(),
+                        offset,
+                        TextStyle::from(("sans-serif", 10).into_font()).color(&color),
+                    ),
+            ))
+            .unwrap();
+    }
+}
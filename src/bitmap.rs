@@ -0,0 +1,223 @@
+use std::sync::OnceLock;
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use plotters::backend::BitMapBackend;
+use plotters::drawing::DrawingAreaErrorKind;
+use plotters::drawing::IntoDrawingArea;
+use plotters::prelude::*;
+use plotters_backend::text_anchor::{HPos, VPos};
+use plotters_backend::{
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingErrorKind,
+};
+
+use crate::error::SnapshotError;
+
+/// Font of all PNG text, embedded so output doesn't depend on system fonts
+///
+/// Subset of DejaVu Sans covering Latin-1 and common symbols, see `assets/fonts/LICENSE-DejaVu.txt`
+const FONT_DATA: &[u8] = include_bytes!("../assets/fonts/DejaVuSans.ttf");
+
+fn font() -> &'static FontRef<'static> {
+    static FONT: OnceLock<FontRef<'static>> = OnceLock::new();
+    FONT.get_or_init(|| FontRef::try_from_slice(FONT_DATA).expect("embedded font is valid"))
+}
+
+/// Bitmap backend drawing text with the embedded font
+///
+/// Text size is estimated the same way as for SVG charts, so both share one layout
+pub(crate) struct PngBackend<'a> {
+    inner: BitMapBackend<'a>,
+}
+
+/// Result of drawing onto a PNG chart
+pub(crate) type DrawingResult = Result<(), DrawingError>;
+
+type DrawingError = DrawingAreaErrorKind<<BitMapBackend<'static> as DrawingBackend>::ErrorType>;
+
+type BackendResult<'a> =
+    Result<(), DrawingErrorKind<<BitMapBackend<'a> as DrawingBackend>::ErrorType>>;
+
+impl<'a> DrawingBackend for PngBackend<'a> {
+    type ErrorType = <BitMapBackend<'a> as DrawingBackend>::ErrorType;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.inner.get_size()
+    }
+
+    fn ensure_prepared(&mut self) -> BackendResult<'a> {
+        self.inner.ensure_prepared()
+    }
+
+    fn present(&mut self) -> BackendResult<'a> {
+        self.inner.present()
+    }
+
+    fn draw_pixel(&mut self, point: BackendCoord, color: BackendColor) -> BackendResult<'a> {
+        self.inner.draw_pixel(point, color)
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> BackendResult<'a> {
+        self.inner.draw_line(from, to, style)
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> BackendResult<'a> {
+        self.inner.draw_rect(upper_left, bottom_right, style, fill)
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> BackendResult<'a> {
+        self.inner.draw_path(path, style)
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> BackendResult<'a> {
+        self.inner.draw_circle(center, radius, style, fill)
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> BackendResult<'a> {
+        self.inner.fill_polygon(vert, style)
+    }
+
+    fn draw_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: BackendCoord,
+    ) -> BackendResult<'a> {
+        let color = style.color();
+        if color.alpha == 0.0 {
+            return Ok(());
+        }
+
+        // Same font size as the SVG backend writes
+        let font = font();
+        let scaled = font.as_scaled(PxScale::from((style.size() / 1.24) as f32));
+
+        let mut caret = 0.0;
+        let mut previous = None;
+        let mut glyphs = Vec::new();
+        for c in text.chars() {
+            let id = scaled.glyph_id(c);
+            if let Some(previous) = previous {
+                caret += scaled.kern(previous, id);
+            }
+            glyphs.push(id.with_scale_and_position(scaled.scale(), point(caret, scaled.ascent())));
+            caret += scaled.h_advance(id);
+            previous = Some(id);
+        }
+
+        let width = caret.ceil() as i32;
+        let height = (scaled.ascent() - scaled.descent()).ceil() as i32;
+        let anchor = style.anchor();
+        let dx = match anchor.h_pos {
+            HPos::Left => 0,
+            HPos::Right => -width,
+            HPos::Center => -width / 2,
+        };
+        let dy = match anchor.v_pos {
+            VPos::Top => 0,
+            VPos::Center => -height / 2,
+            VPos::Bottom => -height,
+        };
+        let transform = style.transform();
+        let (w, h) = self.get_size();
+
+        for glyph in glyphs {
+            let Some(outlined) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outlined.px_bounds();
+            let mut result = Ok(());
+            outlined.draw(|x, y, coverage| {
+                let (x, y) = transform.transform(
+                    bounds.min.x as i32 + x as i32 + dx,
+                    bounds.min.y as i32 + y as i32 + dy,
+                );
+                let (x, y) = (pos.0 + x, pos.1 + y);
+                if result.is_ok()
+                    && coverage > 0.0
+                    && x >= 0
+                    && y >= 0
+                    && x < w as i32
+                    && y < h as i32
+                {
+                    result = self.inner.draw_pixel(
+                        (x, y),
+                        BackendColor {
+                            alpha: color.alpha * coverage.min(1.0) as f64,
+                            rgb: color.rgb,
+                        },
+                    );
+                }
+            });
+            result?;
+        }
+
+        Ok(())
+    }
+
+    fn blit_bitmap(
+        &mut self,
+        pos: BackendCoord,
+        size: (u32, u32),
+        src: &[u8],
+    ) -> BackendResult<'a> {
+        self.inner.blit_bitmap(pos, size, src)
+    }
+}
+
+/// Draw a `width x height` chart with `draw` and encode it as PNG
+pub(crate) fn render_png(
+    (width, height): (u32, u32),
+    draw: impl FnOnce(&DrawingArea<PngBackend<'_>, plotters::coord::Shift>) -> DrawingResult,
+) -> Result<Vec<u8>, SnapshotError> {
+    let drawing_error =
+        |err: DrawingError| SnapshotError::Png(std::io::Error::other(err.to_string()));
+
+    let mut pixels = vec![0u8; width as usize * height as usize * 3];
+    {
+        let root = PngBackend {
+            inner: BitMapBackend::with_buffer(&mut pixels, (width, height)),
+        }
+        .into_drawing_area();
+        draw(&root).map_err(drawing_error)?;
+        root.present().map_err(drawing_error)?;
+    }
+
+    let mut png_buffer = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut png_buffer, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|err| SnapshotError::Png(err.into()))?;
+        writer
+            .write_image_data(&pixels)
+            .map_err(|err| SnapshotError::Png(err.into()))?;
+    }
+    Ok(png_buffer)
+}
@@ -1,15 +1,29 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
 use plotters::backend::SVGBackend;
+use plotters::coord::types::RangedCoordf64;
 use plotters::drawing::IntoDrawingArea;
-use plotters::element::DashedPathElement;
 use plotters::prelude::*;
 
-use crate::abnormal::{AbnormalSample, abnormal_smaples_series};
+use crate::abnormal::abnormal_smaples_series;
+use crate::annotation::{draw_markers, draw_regions};
+#[cfg(feature = "png")]
+use crate::bitmap::render_png;
 use crate::chart_data::ChannelChartData;
-use crate::config::SvgChartConfig;
+use crate::config::{SvgChartConfig, YRange, YScale};
+use crate::data::SnapshotData;
+use crate::decimation::{rms_envelope, waveform_points};
+#[cfg(feature = "png")]
+use crate::error::SnapshotError;
+use crate::series::draw_waveform_series;
+use crate::statistics::{draw_footer, draw_statistics_legend, footer_height};
+use crate::svg_path::simplify_paths;
 use crate::util::{
     INPUT_CHANNEL_COLORS, OUTPUT_CHANNEL_COLORS, get_contrasting_color, num_x_labels,
     parse_hex_color, time_formatter,
 };
+use crate::xy_chart::{ChartChannel, xy_chart};
 
 /// Chart layout
 ///
@@ -25,136 +39,329 @@ pub enum Layout {
     CombinedPerChannelType,
     /// All channels plot on one chart
     Combined,
+    /// Channel `y` plots against channel `x` on one square chart, e.g. left against right
+    /// or input against output of a waveshaper
+    ///
+    /// With `mid_side` the chart rotates by 45°, mid on the vertical axis and side on the horizontal one.
+    /// Charts which can't plot channel pairs, e.g. frequency charts, plot channels separately
+    XY {
+        /// Channel along the horizontal axis
+        x: ChartChannel,
+        /// Channel along the vertical axis
+        y: ChartChannel,
+        /// Rotate by 45° to show mid and side
+        mid_side: bool,
+    },
 }
 
-pub(crate) fn generate_svg(
-    input_data: &[Vec<f32>],
-    output_data: &[Vec<f32>],
-    abnormalities: &[Vec<(usize, AbnormalSample)>],
-    config: &SvgChartConfig,
-    sample_rate: f64,
-    num_samples: usize,
-    start_sample: usize,
-) -> String {
-    let height_per_channel = config.svg_height_per_channel;
-    let num_channels = output_data.len()
+/// Size of waveform chart in pixels, `None` when there is nothing to plot
+fn chart_size(data: &SnapshotData, config: &SvgChartConfig) -> Option<(u32, u32)> {
+    let num_channels = data.outputs.len()
         + if config.with_inputs {
-            input_data.len()
+            data.inputs.len()
         } else {
             0
         };
 
-    if num_samples == 0 || num_channels == 0 {
-        return "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\"><text>Empty</text></svg>".to_string();
+    if data.num_samples == 0 || num_channels == 0 {
+        return None;
+    }
+
+    // Pair of channels on one square chart
+    if let Layout::XY { .. } = config.chart_layout {
+        let height = config.svg_height_per_channel as u32;
+        let width = config.svg_width.map_or(height, |width| width as u32);
+        return Some((
+            width,
+            height + footer_height(config, footer_rows(config, 0)),
+        ));
+    }
+
+    let svg_width = config
+        .svg_width
+        .unwrap_or_else(|| config.decimation.default_width(data.num_samples))
+        as u32;
+    let total_height = (config.svg_height_per_channel * num_channels) as u32;
+    Some((
+        svg_width,
+        total_height + footer_height(config, footer_rows(config, num_channels)),
+    ))
+}
+
+/// Rows of statistics footer, plotted pair in X-Y layout or all `num_channels` otherwise
+fn footer_rows(config: &SvgChartConfig, num_channels: usize) -> usize {
+    match config.chart_layout {
+        Layout::XY { .. } => 2,
+        _ => num_channels,
     }
+}
 
-    let svg_width = config.svg_width.unwrap_or(num_samples * 2) as u32;
-    let total_height = (height_per_channel * num_channels) as u32;
+pub(crate) fn generate_svg(data: &SnapshotData, config: &SvgChartConfig) -> String {
+    let data = &apply_viewport(data, config);
+    let Some((svg_width, total_height)) = chart_size(data, config) else {
+        return "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\"><text>Empty</text></svg>".to_string();
+    };
 
     // Create SVG backend with buffer
     let mut svg_buffer = String::new();
     {
         let root =
             SVGBackend::with_string(&mut svg_buffer, (svg_width, total_height)).into_drawing_area();
+        draw_chart(&root, data, config);
+    }
 
-        // Fill background
-        let bg_color = parse_hex_color(&config.background_color);
-        root.fill(&bg_color).unwrap();
+    finish_svg(svg_buffer, config, svg_width, total_height)
+}
 
-        // Add optional title with contrasting color
-        let current_area = if let Some(ref title) = config.chart_title {
-            let title_color = get_contrasting_color(&bg_color);
-            let text_style = TextStyle::from(("sans-serif", 20)).color(&title_color);
-            root.titled(title, text_style).unwrap()
-        } else {
-            root
-        };
+/// Render waveform chart as PNG with the same layout as [generate_svg]
+#[cfg(feature = "png")]
+pub(crate) fn generate_png(
+    data: &SnapshotData,
+    config: &SvgChartConfig,
+) -> Result<Vec<u8>, SnapshotError> {
+    let data = &apply_viewport(data, config);
+    match chart_size(data, config) {
+        Some(size) => render_png(size, |root| {
+            draw_chart(root, data, config);
+            Ok(())
+        }),
+        None => render_png((100, 100), |root| {
+            root.fill(&parse_hex_color(&config.background_color))
+        }),
+    }
+}
+
+/// Captured samples within `config.viewport`, with X axis offset moved to its start
+fn apply_viewport<'a>(data: &'a SnapshotData, config: &SvgChartConfig) -> Cow<'a, SnapshotData> {
+    let Some(viewport) = &config.viewport else {
+        return Cow::Borrowed(data);
+    };
+    let end = viewport.end.min(data.num_samples);
+    let start = viewport.start.min(end);
+    let window = |channels: &[Vec<f32>]| -> Vec<Vec<f32>> {
+        channels
+            .iter()
+            .map(|samples| samples_in(samples, &(start..end)).to_vec())
+            .collect()
+    };
+
+    Cow::Owned(SnapshotData {
+        inputs: window(&data.inputs),
+        outputs: window(&data.outputs),
+        abnormalities: data
+            .abnormalities
+            .iter()
+            .map(|abnormalities| {
+                abnormalities
+                    .iter()
+                    .filter(|(i, _)| (start..end).contains(i))
+                    .map(|&(i, abnormality)| (i - start, abnormality))
+                    .collect()
+            })
+            .collect(),
+        num_samples: end - start,
+        sample_rate: data.sample_rate,
+        start_sample: data.start_sample + start,
+        processing_mode: data.processing_mode,
+        frequency_responses: Vec::new(),
+    })
+}
 
-        let input_charts: Vec<ChannelChartData> = if config.with_inputs {
-            input_data
-                .iter()
-                .enumerate()
-                .map(|(i, data)| ChannelChartData::from_input_data(data, i, config))
-                .collect()
+/// Samples of `channel_data` within `range`, clamped to its length
+fn samples_in<'a>(channel_data: &'a [f32], range: &Range<usize>) -> &'a [f32] {
+    let end = range.end.min(channel_data.len());
+    &channel_data[range.start.min(end)..end]
+}
+
+fn draw_chart<DB: DrawingBackend>(
+    root: &DrawingArea<DB, plotters::coord::Shift>,
+    data: &SnapshotData,
+    config: &SvgChartConfig,
+) {
+    let SnapshotData {
+        inputs: input_data,
+        outputs: output_data,
+        abnormalities,
+        sample_rate,
+        start_sample,
+        ..
+    } = data;
+    let (sample_rate, start_sample) = (*sample_rate, *start_sample);
+    let num_channels = output_data.len()
+        + if config.with_inputs {
+            input_data.len()
         } else {
-            vec![]
+            0
         };
 
-        let output_charts: Vec<ChannelChartData> = output_data
+    // Fill background
+    let bg_color = parse_hex_color(&config.background_color);
+    root.fill(&bg_color).unwrap();
+
+    // Add optional title with contrasting color
+    let current_area = if let Some(ref title) = config.chart_title {
+        let title_color = get_contrasting_color(&bg_color);
+        let text_style = TextStyle::from(("sans-serif", 20)).color(&title_color);
+        root.titled(title, text_style).unwrap()
+    } else {
+        root.clone()
+    };
+
+    let input_charts: Vec<ChannelChartData> = if config.with_inputs {
+        input_data
             .iter()
-            .zip(abnormalities)
             .enumerate()
-            .map(|(i, (data, abnormalities))| {
-                ChannelChartData::from_output_data(data, abnormalities, i, config)
-            })
-            .collect();
-
-        let output_axis_color = parse_hex_color(OUTPUT_CHANNEL_COLORS[0]);
-        let input_axis_color = parse_hex_color(INPUT_CHANNEL_COLORS[0]);
-
-        match config.chart_layout {
-            Layout::SeparateChannels => {
-                // Split area for each channel
-                let areas = current_area.split_evenly((num_channels, 1));
-                for (chart, area) in input_charts
-                    .into_iter()
-                    .chain(output_charts.into_iter())
-                    .zip(areas)
-                {
-                    one_channel_chart(chart, config, start_sample, &area, sample_rate);
-                }
-            }
-            Layout::CombinedPerChannelType => {
-                if config.with_inputs {
-                    let areas = current_area.split_evenly((2, 1));
-
-                    multi_channel_chart(
-                        input_charts,
-                        config,
-                        true,
-                        start_sample,
-                        input_axis_color,
-                        &areas[0],
-                        sample_rate,
-                    );
-                    multi_channel_chart(
-                        output_charts,
-                        config,
-                        true,
-                        start_sample,
-                        output_axis_color,
-                        &areas[1],
-                        sample_rate,
-                    );
-                } else {
-                    multi_channel_chart(
-                        output_charts,
-                        config,
-                        true,
-                        start_sample,
-                        output_axis_color,
-                        &current_area,
-                        sample_rate,
-                    );
-                }
+            .map(|(i, data)| ChannelChartData::from_input_data(data, i, config))
+            .collect()
+    } else {
+        vec![]
+    };
+
+    let output_charts: Vec<ChannelChartData> = output_data
+        .iter()
+        .zip(abnormalities)
+        .enumerate()
+        .map(|(i, (data, abnormalities))| {
+            ChannelChartData::from_output_data(data, abnormalities, i, config)
+        })
+        .collect();
+
+    // Statistics table under all charts, of the plotted pair in X-Y layout,
+    // and loudness of all outputs
+    let current_area = match footer_height(config, footer_rows(config, num_channels)) {
+        0 => current_area,
+        height => {
+            let charts_height = current_area.dim_in_pixel().1.saturating_sub(height);
+            let (charts_area, footer_area) = current_area.split_vertically(charts_height);
+            let xy_charts: Vec<ChannelChartData> = match config.chart_layout {
+                Layout::XY { x, y, .. } => [x, y]
+                    .iter()
+                    .filter_map(|channel| channel.chart_data(data, config))
+                    .collect(),
+                _ => vec![],
+            };
+            let rows: Vec<&ChannelChartData> = match config.chart_layout {
+                Layout::XY { .. } => xy_charts.iter().collect(),
+                _ => input_charts.iter().chain(&output_charts).collect(),
+            };
+            let loudness = config.show_loudness.then(|| data.loudness());
+            draw_footer(&rows, loudness, config, start_sample, &footer_area);
+            charts_area
+        }
+    };
+
+    let output_axis_color = parse_hex_color(OUTPUT_CHANNEL_COLORS[0]);
+    let input_axis_color = parse_hex_color(INPUT_CHANNEL_COLORS[0]);
+
+    let shared_y_range = (config.y_range == YRange::Shared).then(|| {
+        let samples = input_charts
+            .iter()
+            .chain(&output_charts)
+            .flat_map(|chart| chart.data.iter());
+        auto_y_range(samples, config)
+    });
+    // Inset range counts from the first captured sample, same as viewport
+    let viewport_start = config
+        .viewport
+        .as_ref()
+        .map_or(0, |viewport| viewport.start);
+    let inset = config
+        .inset
+        .as_ref()
+        .map(|inset| {
+            inset
+                .start
+                .saturating_sub(viewport_start)
+                .min(data.num_samples)
+                ..inset
+                    .end
+                    .saturating_sub(viewport_start)
+                    .min(data.num_samples)
+        })
+        .filter(|inset| !inset.is_empty());
+    let axes = Axes {
+        start_from: start_sample,
+        sample_rate,
+        shared_y_range,
+        inset,
+    };
+
+    match config.chart_layout {
+        Layout::SeparateChannels => {
+            // Split area for each channel
+            let areas = current_area.split_evenly((num_channels, 1));
+            for (chart, area) in input_charts.into_iter().chain(output_charts).zip(areas) {
+                one_channel_chart(chart, config, &axes, &area);
             }
-            Layout::Combined => {
-                let charts = output_charts.into_iter().chain(input_charts).collect();
+        }
+        Layout::CombinedPerChannelType => {
+            if config.with_inputs {
+                let areas = current_area.split_evenly((2, 1));
+
+                multi_channel_chart(
+                    input_charts,
+                    config,
+                    true,
+                    &axes,
+                    input_axis_color,
+                    &areas[0],
+                );
                 multi_channel_chart(
-                    charts,
+                    output_charts,
                     config,
-                    false,
-                    start_sample,
+                    true,
+                    &axes,
+                    output_axis_color,
+                    &areas[1],
+                );
+            } else {
+                multi_channel_chart(
+                    output_charts,
+                    config,
+                    true,
+                    &axes,
                     output_axis_color,
                     &current_area,
-                    sample_rate,
                 );
             }
         }
-
-        current_area.present().unwrap();
+        Layout::XY { x, y, mid_side } => {
+            xy_chart(data, config, (x, y), mid_side, &current_area);
+        }
+        Layout::Combined => {
+            let charts = output_charts.into_iter().chain(input_charts).collect();
+            multi_channel_chart(
+                charts,
+                config,
+                false,
+                &axes,
+                output_axis_color,
+                &current_area,
+            );
+        }
     }
 
+    current_area.present().unwrap();
+}
+
+/// Apply configured post-processing to a rendered SVG
+pub(crate) fn finish_svg(
+    svg_buffer: String,
+    config: &SvgChartConfig,
+    svg_width: u32,
+    total_height: u32,
+) -> String {
+    let svg_buffer = simplify_paths(svg_buffer, config);
+    apply_preserve_aspect_ratio(svg_buffer, config, svg_width, total_height)
+}
+
+/// Replace fixed SVG size with `preserveAspectRatio` when configured
+fn apply_preserve_aspect_ratio(
+    svg_buffer: String,
+    config: &SvgChartConfig,
+    svg_width: u32,
+    total_height: u32,
+) -> String {
     if let Some(preserve_aspect_ratio) = config.preserve_aspect_ratio {
         svg_buffer.replace(
             format!(r#"<svg width="{svg_width}" height="{total_height}" "#).as_str(),
@@ -168,34 +375,296 @@ pub(crate) fn generate_svg(
     }
 }
 
-fn multi_channel_chart(
+/// Position of waveform charts on the X axis, Y range shared between them
+/// and magnified inset range in sample indices
+struct Axes {
+    start_from: usize,
+    sample_rate: f64,
+    shared_y_range: Option<(f64, f64)>,
+    inset: Option<Range<usize>>,
+}
+
+/// Y axis range of one chart plotting `samples`, as configured by `config.y_range`
+fn y_range<'a>(
+    samples: impl Iterator<Item = &'a f32>,
+    config: &SvgChartConfig,
+    axes: &Axes,
+) -> (f64, f64) {
+    match (config.y_range, axes.shared_y_range) {
+        (YRange::Fixed { min, max }, _) => {
+            let (min, max) = (config.y_scale.apply(min), config.y_scale.apply(max));
+            let y_min = config.y_scale.floor().unwrap_or(min.min(max) as f64);
+            (y_min, min.max(max) as f64)
+        }
+        (YRange::Shared, Some(shared)) => shared,
+        _ => auto_y_range(samples, config),
+    }
+}
+
+/// Y axis range of `samples` on the configured Y scale, padded by 10%
+fn auto_y_range<'a>(samples: impl Iterator<Item = &'a f32>, config: &SvgChartConfig) -> (f64, f64) {
+    let (min_val, max_val) = samples
+        .map(|&sample| config.y_scale.apply(sample))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), sample| {
+            (min.min(sample), max.max(sample))
+        });
+    let range = (max_val - min_val).max(f32::EPSILON);
+    let y_min = (min_val - range * 0.1) as f64;
+    let y_max = (max_val + range * 0.1) as f64;
+
+    match config.y_scale.floor() {
+        // Silence spans the whole range from floor to full scale
+        Some(floor) if max_val as f64 <= floor => (floor, 0.0_f64.max(floor + 1.0)),
+        Some(floor) => (y_min.max(floor), y_max),
+        None => (y_min, y_max),
+    }
+}
+
+/// Group channels into charts according to `config.chart_layout`
+///
+/// Used by charts which plot every group the same way, e.g. frequency charts
+pub(crate) fn layout_groups(
+    config: &SvgChartConfig,
+    input_charts: Vec<ChannelChartData>,
+    output_charts: Vec<ChannelChartData>,
+) -> Vec<Vec<ChannelChartData>> {
+    match config.chart_layout {
+        Layout::SeparateChannels | Layout::XY { .. } => input_charts
+            .into_iter()
+            .chain(output_charts)
+            .map(|chart| vec![chart])
+            .collect(),
+        Layout::CombinedPerChannelType => {
+            if input_charts.is_empty() {
+                vec![output_charts]
+            } else {
+                vec![input_charts, output_charts]
+            }
+        }
+        Layout::Combined => vec![output_charts.into_iter().chain(input_charts).collect()],
+    }
+}
+
+/// Axis color of a chart group
+///
+/// Channel color for a single channel, first palette color of the channel type otherwise
+pub(crate) fn group_axis_color(group: &[ChannelChartData]) -> RGBColor {
+    match group {
+        [single] => single.color,
+        _ if group.iter().all(|chart| chart.is_input) => parse_hex_color(INPUT_CHANNEL_COLORS[0]),
+        _ => parse_hex_color(OUTPUT_CHANNEL_COLORS[0]),
+    }
+}
+
+/// Waveform path of one channel on the configured Y scale,
+/// decimated to `columns` pixel columns when configured
+fn waveform(
+    channel_data: &[f32],
+    config: &SvgChartConfig,
+    start_from: usize,
+    columns: usize,
+) -> Vec<(f64, f64)> {
+    let scaled = config.y_scale.apply_all(channel_data);
+    waveform_points(&scaled, config.decimation, columns)
+        .into_iter()
+        .map(|(i, sample)| ((i + start_from) as f64, sample as f64))
+        .collect()
+}
+
+/// Shade the area beyond ±1.0 full scale, when configured
+fn draw_clipping_band<DB: DrawingBackend>(
+    chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    config: &SvgChartConfig,
+    (y_min, y_max): (f64, f64),
+) {
+    if !config.show_clipping {
+        return;
+    }
+
+    let x_range = chart.x_range();
+    let upper = config.y_scale.apply(1.0) as f64;
+    let lower = config.y_scale.apply(-1.0) as f64;
+    let mut bands = Vec::new();
+    if y_max > upper {
+        bands.push((upper, y_max));
+    }
+    // Magnitude scales fold -1.0 onto +1.0
+    if y_min < lower && lower < upper {
+        bands.push((y_min, lower));
+    }
+
+    for (from, to) in bands {
+        let edge = if from == upper { from } else { to };
+        chart
+            .draw_series([
+                Rectangle::new(
+                    [(x_range.start, from), (x_range.end, to)],
+                    RED.mix(0.15).filled(),
+                )
+                .into_dyn(),
+                PathElement::new([(x_range.start, edge), (x_range.end, edge)], RED.mix(0.5))
+                    .into_dyn(),
+            ])
+            .unwrap();
+    }
+}
+
+/// Highlight samples magnified by the inset, when configured
+fn draw_inset_region<DB: DrawingBackend>(
+    chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    config: &SvgChartConfig,
+    axes: &Axes,
+    (y_min, y_max): (f64, f64),
+) {
+    let Some(inset) = &axes.inset else {
+        return;
+    };
+
+    let background = parse_hex_color(&config.background_color);
+    let color = get_contrasting_color(&background);
+    let (from, to) = (
+        (axes.start_from + inset.start) as f64,
+        (axes.start_from + inset.end) as f64,
+    );
+    chart
+        .draw_series(std::iter::once(Rectangle::new(
+            [(from, y_min), (to, y_max)],
+            color.mix(0.1).filled(),
+        )))
+        .unwrap();
+}
+
+/// Draw magnified inset of `charts_data` in the top right corner of `area`, when configured
+fn draw_inset<DB: DrawingBackend>(
+    charts_data: &[ChannelChartData],
+    config: &SvgChartConfig,
+    axes: &Axes,
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+) {
+    let Some(inset) = &axes.inset else {
+        return;
+    };
+
+    let (width, height) = area.dim_in_pixel();
+    let (inset_width, inset_height) = (width * 2 / 5, height * 2 / 5);
+    let inset_area = area.clone().shrink(
+        (width.saturating_sub(inset_width + 10), 10),
+        (inset_width, inset_height),
+    );
+    let background = parse_hex_color(&config.background_color);
+    let foreground = get_contrasting_color(&background);
+    inset_area.fill(&background).unwrap();
+    inset_area
+        .draw(&Rectangle::new(
+            [(0, 0), (inset_width as i32 - 1, inset_height as i32 - 1)],
+            foreground.mix(0.5),
+        ))
+        .unwrap();
+
+    let (y_min, y_max) = y_range(
+        charts_data
+            .iter()
+            .flat_map(|c| samples_in(&c.data, inset).iter()),
+        config,
+        axes,
+    );
+    let start_from = axes.start_from + inset.start;
+
+    let mut chart = ChartBuilder::on(&inset_area)
+        .margin(5)
+        .x_label_area_size(if config.show_labels { 20 } else { 0 })
+        .y_label_area_size(if config.show_labels { 40 } else { 0 })
+        .build_cartesian_2d(
+            start_from as f64..(axes.start_from + inset.end) as f64,
+            y_min..y_max,
+        )
+        .unwrap();
+
+    let mut mesh = chart.configure_mesh();
+    mesh.disable_mesh().axis_style(foreground.mix(0.3));
+    if config.show_labels {
+        mesh.x_labels(3)
+            .y_labels(3)
+            .label_style(("sans-serif", 10, &foreground));
+    }
+    let formatter = |v: &f64| time_formatter(*v as usize, axes.sample_rate);
+    if config.format_x_axis_labels_as_time {
+        mesh.x_label_formatter(&formatter);
+    }
+    let y_formatter = |v: &f64| config.y_scale.label(*v).unwrap_or_default();
+    if config.y_scale != YScale::Linear {
+        mesh.y_label_formatter(&y_formatter);
+    }
+    mesh.draw().unwrap();
+    draw_clipping_band(&mut chart, config, (y_min, y_max));
+
+    let columns = chart.plotting_area().dim_in_pixel().0 as usize;
+    for entry in charts_data {
+        let samples = samples_in(&entry.data, inset);
+        draw_waveform_series(
+            &mut chart,
+            waveform(samples, config, start_from, columns),
+            config.series_style.resolve(samples.len(), columns),
+            config,
+            entry.color,
+            false,
+            None,
+        );
+    }
+}
+
+/// Shade RMS envelope of each pixel column over the waveform, when configured
+fn draw_rms_envelope<DB: DrawingBackend>(
+    chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    channel_data: &[f32],
+    config: &SvgChartConfig,
+    start_from: usize,
+    columns: usize,
+) {
+    let envelope = rms_envelope(channel_data, config.decimation, columns);
+    if envelope.is_empty() {
+        return;
+    }
+
+    let background = parse_hex_color(&config.background_color);
+    let color = get_contrasting_color(&background).mix(0.35).filled();
+    let upper = envelope.iter().flat_map(|&(first, last, rms)| {
+        let rms = config.y_scale.apply(rms);
+        [(first, rms), (last, rms)]
+    });
+    let lower = envelope.iter().rev().flat_map(|&(first, last, rms)| {
+        let rms = config.y_scale.rms_lower(rms);
+        [(last, rms), (first, rms)]
+    });
+    let points: Vec<(f64, f64)> = upper
+        .chain(lower)
+        .map(|(i, value)| ((i + start_from) as f64, value as f64))
+        .collect();
+
+    chart
+        .draw_series(std::iter::once(Polygon::new(points, color)))
+        .unwrap();
+}
+
+fn multi_channel_chart<DB: DrawingBackend>(
     charts_data: Vec<ChannelChartData>,
     config: &SvgChartConfig,
     solid_input: bool,
-    start_from: usize,
+    axes: &Axes,
     axis_color: RGBColor,
-    area: &DrawingArea<SVGBackend<'_>, plotters::coord::Shift>,
-    sample_rate: f64,
+    area: &DrawingArea<DB, plotters::coord::Shift>,
 ) {
+    let Axes {
+        start_from,
+        sample_rate,
+        ..
+    } = *axes;
     let num_samples = charts_data
         .iter()
         .map(|chart| chart.data.len())
         .max()
         .unwrap_or_default();
-    let min_val = charts_data
-        .iter()
-        .flat_map(|c| c.data.iter())
-        .cloned()
-        .fold(f32::INFINITY, f32::min);
-    let max_val = charts_data
-        .iter()
-        .flat_map(|c| c.data.iter())
-        .cloned()
-        .fold(f32::NEG_INFINITY, f32::max);
-
-    let range = (max_val - min_val).max(f32::EPSILON);
-    let y_min = (min_val - range * 0.1) as f64;
-    let y_max = (max_val + range * 0.1) as f64;
+    let (y_min, y_max) = y_range(charts_data.iter().flat_map(|c| c.data.iter()), config, axes);
 
     // Build chart
     let mut chart = ChartBuilder::on(area)
@@ -235,85 +704,61 @@ fn multi_channel_chart(
     if config.format_x_axis_labels_as_time {
         mesh.x_label_formatter(&formatter);
     }
+    let y_formatter = |v: &f64| config.y_scale.label(*v).unwrap_or_default();
+    if config.y_scale != YScale::Linear {
+        mesh.y_label_formatter(&y_formatter);
+    }
 
     mesh.draw().unwrap();
+    draw_clipping_band(&mut chart, config, (y_min, y_max));
+    draw_inset_region(&mut chart, config, axes, (y_min, y_max));
+    draw_regions(&mut chart, config, sample_rate);
 
     let mut has_legend = false;
+    let columns = chart.plotting_area().dim_in_pixel().0 as usize;
 
     // Draw outputs (or inputs as solid when `solid_input` is true) one by one,
     // registering a legend entry per series.
     for entry in charts_data.iter().filter(|d| !d.is_input || solid_input) {
-        let ChannelChartData {
-            data: channel_data,
-            color,
-            label,
-            ..
-        } = entry;
-
-        let line_style = ShapeStyle {
-            color: color.to_rgba(),
-            filled: false,
-            stroke_width: config.line_width as u32,
-        };
-
-        let series = chart
-            .draw_series(std::iter::once(PathElement::new(
-                channel_data
-                    .iter()
-                    .enumerate()
-                    .map(|(i, &sample)| ((i + start_from) as f64, sample as f64))
-                    .collect::<Vec<(f64, f64)>>(),
-                line_style,
-            )))
-            .unwrap();
-
-        if let Some(label) = label {
-            series
-                .label(label)
-                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], entry.color));
-            has_legend = true;
-        }
+        has_legend |= draw_waveform_series(
+            &mut chart,
+            waveform(&entry.data, config, start_from, columns),
+            config.series_style.resolve(entry.data.len(), columns),
+            config,
+            entry.color,
+            false,
+            entry.label.as_deref(),
+        );
     }
 
     // Dashed inputs when not solid
     if !solid_input && charts_data.iter().any(|d| d.is_input) {
         for entry in charts_data.iter().filter(|d| d.is_input) {
-            let ChannelChartData {
-                data: channel_data,
-                color,
-                label,
-                ..
-            } = entry;
-
-            let line_style = ShapeStyle {
-                color: color.to_rgba(),
-                filled: false,
-                stroke_width: config.line_width as u32,
-            };
-
-            let dashed = DashedPathElement::new(
-                channel_data
-                    .iter()
-                    .enumerate()
-                    .map(|(i, &sample)| ((i + start_from) as f64, sample as f64))
-                    .collect::<Vec<(f64, f64)>>(),
-                2,
-                3,
-                line_style,
+            has_legend |= draw_waveform_series(
+                &mut chart,
+                waveform(&entry.data, config, start_from, columns),
+                config.series_style.resolve(entry.data.len(), columns),
+                config,
+                entry.color,
+                true,
+                entry.label.as_deref(),
             );
-
-            let series = chart.draw_series(std::iter::once(dashed)).unwrap();
-
-            if let Some(label) = label {
-                series.label(label).legend(|(x, y)| {
-                    DashedPathElement::new(vec![(x, y), (x + 20, y)], 2, 3, entry.color)
-                });
-                has_legend = true;
-            }
         }
     }
 
-    abnormal_smaples_series(&charts_data, &mut chart, y_min, y_max);
+    for entry in &charts_data {
+        draw_rms_envelope(&mut chart, &entry.data, config, start_from, columns);
+    }
+
+    draw_markers(&mut chart, config, sample_rate);
+    abnormal_smaples_series(&charts_data, &mut chart, start_from, y_min, y_max);
+    draw_inset(&charts_data, config, axes, area);
+    draw_statistics_legend(
+        &charts_data,
+        config,
+        start_from,
+        &chart.plotting_area().strip_coord_spec(),
+    );
 
     if has_legend {
         let background = parse_hex_color(&config.background_color);
@@ -329,13 +774,17 @@ fn multi_channel_chart(
     }
 }
 
-fn one_channel_chart(
+fn one_channel_chart<DB: DrawingBackend>(
     chart_data: ChannelChartData,
     config: &SvgChartConfig,
-    start_from: usize,
-    area: &DrawingArea<SVGBackend<'_>, plotters::coord::Shift>,
-    sample_rate: f64,
+    axes: &Axes,
+    area: &DrawingArea<DB, plotters::coord::Shift>,
 ) {
+    let Axes {
+        start_from,
+        sample_rate,
+        ..
+    } = *axes;
     let ChannelChartData {
         data: channel_data,
         color,
@@ -346,14 +795,7 @@ fn one_channel_chart(
     let num_samples = channel_data.len();
 
     // Calculate data range
-    let min_val = channel_data.iter().cloned().fold(f32::INFINITY, f32::min);
-    let max_val = channel_data
-        .iter()
-        .cloned()
-        .fold(f32::NEG_INFINITY, f32::max);
-    let range = (max_val - min_val).max(f32::EPSILON);
-    let y_min = (min_val - range * 0.1) as f64;
-    let y_max = (max_val + range * 0.1) as f64;
+    let (y_min, y_max) = y_range(channel_data.iter(), config, axes);
 
     // Build chart
     let mut chart = ChartBuilder::on(area)
@@ -394,26 +836,37 @@ fn one_channel_chart(
     if config.format_x_axis_labels_as_time {
         mesh.x_label_formatter(&formatter);
     }
+    let y_formatter = |v: &f64| config.y_scale.label(*v).unwrap_or_default();
+    if config.y_scale != YScale::Linear {
+        mesh.y_label_formatter(&y_formatter);
+    }
 
     mesh.draw().unwrap();
+    draw_clipping_band(&mut chart, config, (y_min, y_max));
+    draw_inset_region(&mut chart, config, axes, (y_min, y_max));
+    draw_regions(&mut chart, config, sample_rate);
 
     // Draw waveform
-    let line_style = ShapeStyle {
-        color: color.to_rgba(),
-        filled: false,
-        stroke_width: config.line_width as u32,
-    };
-
-    chart
-        .draw_series(std::iter::once(PathElement::new(
-            channel_data
-                .iter()
-                .enumerate()
-                .map(|(i, &sample)| ((i + start_from) as f64, sample as f64))
-                .collect::<Vec<(f64, f64)>>(),
-            line_style,
-        )))
-        .unwrap();
-
-    abnormal_smaples_series(&[chart_data], &mut chart, y_min, y_max);
+    let columns = chart.plotting_area().dim_in_pixel().0 as usize;
+    draw_waveform_series(
+        &mut chart,
+        waveform(channel_data, config, start_from, columns),
+        config.series_style.resolve(channel_data.len(), columns),
+        config,
+        *color,
+        false,
+        None,
+    );
+    draw_rms_envelope(&mut chart, channel_data, config, start_from, columns);
+
+    let charts_data = [chart_data];
+    draw_markers(&mut chart, config, sample_rate);
+    abnormal_smaples_series(&charts_data, &mut chart, start_from, y_min, y_max);
+    draw_inset(&charts_data, config, axes, area);
+    draw_statistics_legend(
+        &charts_data,
+        config,
+        start_from,
+        &chart.plotting_area().strip_coord_spec(),
+    );
 }
@@ -0,0 +1,301 @@
+use std::path::{Path, PathBuf};
+
+use crate::data::SnapshotData;
+
+/// Tolerance for comparing captured samples against stored ones
+///
+/// Used by `SnapshotOutputMode::Raw` so that tiny float drift
+/// (different CPU, SIMD path or compiler) doesn't fail snapshots
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Tolerance {
+    /// Samples must be equal
+    #[default]
+    Exact,
+    /// Maximum absolute difference between samples
+    Absolute(f32),
+    /// Maximum difference relative to the larger magnitude of the two samples
+    Relative(f32),
+    /// Maximum difference in dB relative to full scale (`1.0`)
+    ///
+    /// E.g. `Db(-96.0)` allows deviations below -96 dBFS
+    Db(f32),
+}
+
+impl Tolerance {
+    /// Whether `actual` sample is within tolerance of `expected`
+    pub fn allows(&self, expected: f32, actual: f32) -> bool {
+        let difference = (actual - expected).abs();
+        match self {
+            Tolerance::Exact => actual == expected,
+            Tolerance::Absolute(max) => difference <= *max,
+            Tolerance::Relative(max) => difference <= *max * expected.abs().max(actual.abs()),
+            Tolerance::Db(db) => difference <= 10f32.powf(-db.abs() / 20.0),
+        }
+    }
+}
+
+impl std::fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tolerance::Exact => write!(f, "exact"),
+            Tolerance::Absolute(max) => write!(f, "absolute {max:e}"),
+            Tolerance::Relative(max) => write!(f, "relative {max:e}"),
+            Tolerance::Db(db) => write!(f, "-{} dBFS", db.abs()),
+        }
+    }
+}
+
+/// First deviation found when comparing captured data
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotMismatch {
+    /// Different number of output channels
+    ChannelCount { expected: usize, actual: usize },
+    /// Different number of samples per channel
+    SampleCount { expected: usize, actual: usize },
+    /// Output sample outside of tolerance
+    Sample {
+        /// Output channel index
+        channel: usize,
+        /// Sample index (not counting warm-up)
+        sample: usize,
+        expected: f32,
+        actual: f32,
+        tolerance: Tolerance,
+    },
+    /// Abnormal sample (`NaN`, `±Infinity`) present in only one of the compared outputs
+    Abnormality { channel: usize, sample: usize },
+}
+
+impl std::fmt::Display for SnapshotMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotMismatch::ChannelCount { expected, actual } => write!(
+                f,
+                "Output channel count mismatch. Expected {expected} channels, got {actual}"
+            ),
+            SnapshotMismatch::SampleCount { expected, actual } => write!(
+                f,
+                "Output length mismatch. Expected {expected} samples per channel, got {actual}"
+            ),
+            SnapshotMismatch::Sample {
+                channel,
+                sample,
+                expected,
+                actual,
+                tolerance,
+            } => write!(
+                f,
+                "Output channel #[{channel}] at sample [{sample}] deviates beyond [{tolerance}] tolerance. Expected [{expected}], got [{actual}], difference [{:e}]",
+                (actual - expected).abs()
+            ),
+            SnapshotMismatch::Abnormality { channel, sample } => write!(
+                f,
+                "Output channel #[{channel}] at sample [{sample}] abnormal samples differ"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotMismatch {}
+
+impl SnapshotData {
+    /// Compare outputs against `expected` data within `tolerance`
+    ///
+    /// Returns the first deviation, scanning samples in order and channels within each sample
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use insta_fun::prelude::*;
+    /// use fundsp::prelude::*;
+    ///
+    /// let config = SnapshotConfig::default();
+    /// let expected = capture_audio_unit(lowpass_hz(1000.0, 0.7), InputSource::impulse(), &config);
+    /// let actual = capture_audio_unit(lowpass_hz(1000.0, 0.7) * 1.0001, InputSource::impulse(), &config);
+    ///
+    /// assert!(actual.compare(&expected, Tolerance::Exact).is_err());
+    /// assert!(actual.compare(&expected, Tolerance::Db(-60.0)).is_ok());
+    /// ```
+    pub fn compare(
+        &self,
+        expected: &SnapshotData,
+        tolerance: Tolerance,
+    ) -> Result<(), SnapshotMismatch> {
+        if self.outputs.len() != expected.outputs.len() {
+            return Err(SnapshotMismatch::ChannelCount {
+                expected: expected.outputs.len(),
+                actual: self.outputs.len(),
+            });
+        }
+        if self.num_samples != expected.num_samples {
+            return Err(SnapshotMismatch::SampleCount {
+                expected: expected.num_samples,
+                actual: self.num_samples,
+            });
+        }
+
+        for sample in 0..self.num_samples {
+            for channel in 0..self.outputs.len() {
+                let is_abnormal = |data: &SnapshotData| {
+                    data.abnormalities
+                        .get(channel)
+                        .and_then(|ch| ch.iter().find(|(i, _)| *i == sample))
+                        .map(|(_, abnormality)| *abnormality)
+                };
+                if is_abnormal(self) != is_abnormal(expected) {
+                    return Err(SnapshotMismatch::Abnormality { channel, sample });
+                }
+
+                let expected_sample = expected.outputs[channel][sample];
+                let actual_sample = self.outputs[channel][sample];
+                if !tolerance.allows(expected_sample, actual_sample) {
+                    return Err(SnapshotMismatch::Sample {
+                        channel,
+                        sample,
+                        expected: expected_sample,
+                        actual: actual_sample,
+                        tolerance,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Intended for internal use only
+///
+/// Used by macros to find the workspace root snapshot paths are relative to, same as insta:
+/// `INSTA_WORKSPACE_ROOT` at runtime or compile time, otherwise the closest ancestor
+/// of `manifest_dir` containing `assertion_file`, which `file!()` gives relative to the workspace
+#[doc(hidden)]
+pub fn __workspace_root(
+    manifest_dir: &str,
+    compile_time_root: Option<&str>,
+    assertion_file: &str,
+) -> PathBuf {
+    if let Ok(root) = std::env::var("INSTA_WORKSPACE_ROOT") {
+        return PathBuf::from(root);
+    }
+    if let Some(root) = compile_time_root {
+        return PathBuf::from(root);
+    }
+    Path::new(manifest_dir)
+        .ancestors()
+        .find(|dir| dir.join(assertion_file).is_file())
+        .unwrap_or(Path::new(manifest_dir))
+        .to_path_buf()
+}
+
+/// Intended for internal use only
+///
+/// Used by macros to name unnamed tolerance snapshots explicitly, so their stored path is known.
+/// Follows insta's naming of unnamed snapshots: the test function name without `test_`,
+/// with `-2`, `-3`, ... appended for following unnamed snapshots of the same test
+#[doc(hidden)]
+pub fn __unnamed_snapshot_name(function_name: &str) -> String {
+    thread_local! {
+        static COUNTERS: std::cell::RefCell<std::collections::HashMap<String, usize>> =
+            Default::default();
+    }
+
+    let count = COUNTERS.with_borrow_mut(|counters| {
+        let count = counters.entry(function_name.to_string()).or_default();
+        *count += 1;
+        *count
+    });
+    let name = function_name.rsplit("::").next().unwrap_or(function_name);
+    let name = name.strip_prefix("test_").unwrap_or(name);
+    if count == 1 {
+        name.to_string()
+    } else {
+        format!("{name}-{count}")
+    }
+}
+
+/// Intended for internal use only
+///
+/// Used by macros to locate a stored binary snapshot, following insta's naming scheme
+#[doc(hidden)]
+pub fn __stored_snapshot_path(
+    workspace: &Path,
+    assertion_file: &str,
+    snapshot_path: &Path,
+    module_path: Option<&str>,
+    file_name: &str,
+    suffix: Option<&str>,
+) -> PathBuf {
+    let (name, extension) = file_name.rsplit_once('.').unwrap_or((file_name, ""));
+
+    let mut snapshot_file = String::new();
+    if let Some(module_path) = module_path {
+        snapshot_file.push_str(&module_path.replace("::", "__"));
+        snapshot_file.push_str("__");
+    }
+    snapshot_file.push_str(&name.replace(['/', '\\'], "__"));
+    if let Some(suffix) = suffix {
+        snapshot_file.push('@');
+        snapshot_file.push_str(suffix);
+    }
+    snapshot_file.push_str(".snap.");
+    snapshot_file.push_str(extension);
+
+    workspace
+        .join(Path::new(assertion_file).parent().unwrap_or(Path::new("")))
+        .join(snapshot_path)
+        .join(snapshot_file)
+}
+
+/// Intended for internal use only
+///
+/// Used by macros to compare raw snapshot data with the stored snapshot within tolerance.
+///
+/// Returns stored bytes when the new data is within tolerance, so the binary snapshot matches.
+/// Otherwise returns new bytes for insta to review, with the reason tolerance didn't apply
+/// (first deviation, missing or undecodable stored snapshot) to report when the assertion fails.
+#[doc(hidden)]
+pub fn __tolerant_snapshot_bytes(
+    stored: &Path,
+    bytes: Vec<u8>,
+    tolerance: Tolerance,
+) -> (Vec<u8>, Option<String>) {
+    let Ok(stored_bytes) = std::fs::read(stored) else {
+        let note = format!(
+            "No stored snapshot at {}, compared exactly instead of within {tolerance} tolerance",
+            stored.display()
+        );
+        return (bytes, Some(note));
+    };
+    if stored_bytes == bytes {
+        return (bytes, None);
+    }
+
+    let expected = match SnapshotData::from_raw(&stored_bytes) {
+        Ok(expected) => expected,
+        Err(error) => {
+            let note = format!("{}: {error}, compared exactly", stored.display());
+            return (bytes, Some(note));
+        }
+    };
+    let Ok(actual) = SnapshotData::from_raw(&bytes) else {
+        return (bytes, None);
+    };
+
+    match actual.compare(&expected, tolerance) {
+        Ok(()) => (stored_bytes, None),
+        Err(mismatch) => (bytes, Some(format!("{}: {mismatch}", stored.display()))),
+    }
+}
+
+/// Intended for internal use only
+///
+/// Used by macros to extend the message of a failed snapshot assertion
+#[doc(hidden)]
+pub fn __panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("snapshot assertion failed")
+}
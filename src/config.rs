@@ -1,11 +1,23 @@
+use std::ops::Range;
 use std::str::FromStr;
 
 use derive_builder::Builder;
 use fundsp::DEFAULT_SR;
 
+use crate::compare::Tolerance;
+use crate::data::SnapshotData;
 use crate::warmup::WarmUp;
 
+pub use crate::annotation::{Annotation, AnnotationKind, ChartPosition};
 pub use crate::chart::Layout;
+pub use crate::decimation::Decimation;
+pub use crate::series::SeriesStyle;
+pub use crate::spectrogram::ColorMap;
+pub use crate::spectrum::WindowFunction;
+pub use crate::statistics::StatisticsDisplay;
+pub use crate::text_chart::TextChartStyle;
+pub use crate::xy_chart::ChartChannel;
+pub use crate::y_scale::{YRange, YScale};
 
 const DEFAULT_HEIGHT: usize = 500;
 
@@ -42,18 +54,42 @@ pub struct SnapshotConfig {
     /// the snapshotting process will panic.
     #[builder(default = "false")]
     pub allow_abnormal_samples: bool,
+    /// Loudness and true-peak limits of captured outputs
+    ///
+    /// When exceeded, the snapshotting process will panic,
+    /// fallible functions return [crate::error::SnapshotError::LoudnessLimitExceeded]
+    ///
+    /// Default - `None`
+    #[builder(default, setter(strip_option))]
+    pub loudness_limits: Option<LoudnessLimits>,
+    /// Store loudness and true-peak of captured outputs in the description of every snapshot,
+    /// e.g. next to WAV snapshots
+    ///
+    /// Default - `false`
+    #[builder(default)]
+    pub loudness_metadata: bool,
 
     /// Snaphsot output mode
     ///
     /// Use configurable chart for visual snapshots
     ///
     /// Use Wav16 or Wav32 for audial snapshots
+    ///
+    /// Use Raw for tolerance-aware comparison of samples
     #[builder(
         default = "SnapshotOutputMode::SvgChart(SvgChartConfig::default())",
         try_setter,
         setter(into)
     )]
     pub output_mode: SnapshotOutputMode,
+
+    /// Additional output modes
+    ///
+    /// Rendered from the same processing run as `output_mode`
+    ///
+    /// Default - empty `Vec`
+    #[builder(default, setter(into, each(into, name = "extra_output_mode")))]
+    pub extra_output_modes: Vec<SnapshotOutputMode>,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -220,7 +256,8 @@ pub struct SvgChartConfig {
     pub with_inputs: bool,
     /// Optional width of the SVG `viewBox`
     ///
-    /// `None` means proportional to num_samples
+    /// `None` means proportional to num_samples,
+    /// capped at 4000 when `decimation` isn't `Decimation::Exact`
     #[builder(default, setter(strip_option))]
     pub svg_width: Option<usize>,
     /// Height of **one** channel in the SVG `viewBox`
@@ -279,6 +316,85 @@ pub struct SvgChartConfig {
     /// Default - 2.0
     #[builder(default = "2.0")]
     pub line_width: f32,
+    /// How waveform samples are drawn: line, stems, points or steps
+    ///
+    /// `SeriesStyle::Auto` draws stems for short signals and a line otherwise
+    ///
+    /// Default - `SeriesStyle::Line`
+    #[builder(default)]
+    pub series_style: SeriesStyle,
+    /// Reduction of waveform samples when they outnumber pixel columns
+    ///
+    /// Use `Decimation::MinMax` to keep charts of long snapshots small
+    ///
+    /// Default - `Decimation::Exact`
+    #[builder(default)]
+    pub decimation: Decimation,
+    /// Scale of the Y axis of waveform charts
+    ///
+    /// `YScale::Decibel` and `YScale::SymLog` show quiet tails and noise floors next to loud peaks
+    ///
+    /// Default - `YScale::Linear`
+    #[builder(default)]
+    pub y_scale: YScale,
+    /// Range of the Y axis of waveform charts
+    ///
+    /// Default - `YRange::Auto`
+    #[builder(default)]
+    pub y_range: YRange,
+    /// Shade the area beyond ±1.0 full scale where samples would clip
+    ///
+    /// Default - `false`
+    #[builder(default)]
+    pub show_clipping: bool,
+    /// Range of captured samples to plot, `0` is the first sample after warm-up
+    ///
+    /// X axis labels keep counting from the start of processing
+    ///
+    /// Default - `None` (all captured samples)
+    #[builder(default, setter(strip_option))]
+    pub viewport: Option<Range<usize>>,
+    /// Range of captured samples to magnify in an inset in the top right corner of every chart
+    ///
+    /// Counts from the first sample after warm-up like `viewport`,
+    /// the magnified range is highlighted on the chart
+    ///
+    /// Default - `None`
+    #[builder(default, setter(strip_option))]
+    pub inset: Option<Range<usize>>,
+    /// Event markers, shaded regions and reference levels drawn on every waveform chart
+    ///
+    /// Default - empty `Vec`
+    #[builder(default, setter(each(name = "annotation")))]
+    pub annotations: Vec<Annotation>,
+    /// Where to show peak, RMS, DC offset, crest factor, zero crossings
+    /// and non-silent range of plotted samples, see [crate::statistics::ChannelStatistics]
+    ///
+    /// Non-silent ranges count from the start of processing, same as X axis labels
+    ///
+    /// Default - `StatisticsDisplay::Hidden`
+    #[builder(default)]
+    pub statistics: StatisticsDisplay,
+    /// Show integrated, short-term and momentary loudness and true-peak
+    /// of plotted outputs under all charts, see [crate::loudness::Loudness]
+    ///
+    /// Default - `false`
+    #[builder(default)]
+    pub show_loudness: bool,
+    /// Round waveform and curve vertices to multiples of this many pixels
+    ///
+    /// Small numeric drift then rarely moves a vertex, keeping snapshots stable
+    ///
+    /// Default - `None` (whole pixels)
+    #[builder(default, setter(strip_option))]
+    pub path_precision: Option<u32>,
+    /// Drop waveform and curve vertices closer than this many pixels to the simplified path
+    ///
+    /// Uses Ramer–Douglas–Peucker simplification, `Some(0.0)` drops only collinear vertices
+    ///
+    /// Default - `None` (keep all vertices)
+    #[builder(default, setter(strip_option))]
+    pub path_simplification: Option<f64>,
 
     // Chart colors
     /// Chart background color (hex string)
@@ -298,6 +414,425 @@ pub struct SvgChartConfig {
     pub input_colors: Option<Vec<String>>,
 }
 
+/// Configuration of magnitude spectrum chart
+///
+/// Plots FFT magnitude of every channel on a logarithmic frequency axis.
+/// Magnitudes are normalized so that a full scale sine reads 0 dB.
+#[derive(Debug, Clone, Builder)]
+pub struct SpectrumConfig {
+    /// Chart colors, titles and layout
+    ///
+    /// `svg_width` defaults to 1000, `format_x_axis_labels_as_time` is ignored
+    ///
+    /// Default - `SvgChartConfig::default()`
+    #[builder(default, setter(into))]
+    pub chart: SvgChartConfig,
+    /// Window function applied to each FFT frame
+    ///
+    /// Default - `WindowFunction::Hann`
+    #[builder(default)]
+    pub window: WindowFunction,
+    /// FFT size, rounded up to a power of two in range `[2..=32768]`
+    ///
+    /// Longer signals are averaged over 50% overlapping frames,
+    /// shorter signals are zero-padded
+    ///
+    /// Default - `None` (next power of two fitting `num_samples`)
+    #[builder(default, setter(strip_option))]
+    pub fft_size: Option<usize>,
+    /// Lowest plotted frequency in Hz
+    ///
+    /// Default - 20.0
+    #[builder(default = "20.0")]
+    pub min_frequency: f64,
+    /// Highest plotted frequency in Hz
+    ///
+    /// Default - `None` (Nyquist frequency)
+    #[builder(default, setter(strip_option))]
+    pub max_frequency: Option<f64>,
+    /// Bottom of the magnitude axis in dB, quieter bins are clamped to it
+    ///
+    /// Default - -120.0
+    #[builder(default = "-120.0")]
+    pub min_db: f32,
+}
+
+/// Configuration of spectrogram chart
+///
+/// Plots short-time Fourier transform of every channel as a heat map
+/// on a logarithmic frequency axis, one chart per channel.
+/// Magnitudes are normalized so that a full scale sine reads 0 dB.
+#[derive(Debug, Clone, Builder)]
+pub struct SpectrogramConfig {
+    /// Chart colors, titles and dimensions
+    ///
+    /// `svg_width` defaults to 1000, `chart_layout` and `show_grid` are ignored
+    ///
+    /// Default - `SvgChartConfig::default()`
+    #[builder(default, setter(into))]
+    pub chart: SvgChartConfig,
+    /// Window function applied to each FFT frame
+    ///
+    /// Default - `WindowFunction::Hann`
+    #[builder(default)]
+    pub window: WindowFunction,
+    /// FFT frame size, rounded up to a power of two in range `[2..=32768]`
+    ///
+    /// Default - 512
+    #[builder(default = "512")]
+    pub fft_size: usize,
+    /// Number of samples between consecutive frames
+    ///
+    /// Default - `None` (quarter of `fft_size`)
+    #[builder(default, setter(strip_option))]
+    pub hop_size: Option<usize>,
+    /// Lowest plotted frequency in Hz
+    ///
+    /// Default - 20.0
+    #[builder(default = "20.0")]
+    pub min_frequency: f64,
+    /// Highest plotted frequency in Hz
+    ///
+    /// Default - `None` (Nyquist frequency)
+    #[builder(default, setter(strip_option))]
+    pub max_frequency: Option<f64>,
+    /// Bottom of the color scale in dB, quieter bins are clamped to it
+    ///
+    /// Default - -120.0
+    #[builder(default = "-120.0")]
+    pub min_db: f32,
+    /// Heat map color map
+    ///
+    /// Default - `ColorMap::Viridis`
+    #[builder(default)]
+    pub color_map: ColorMap,
+}
+
+/// Frequency grid and measurement of a frequency response
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct FrequencyResponseConfig {
+    /// Lowest frequency of the grid in Hz
+    ///
+    /// Default - 20.0
+    #[builder(default = "20.0")]
+    pub min_frequency: f64,
+    /// Highest frequency of the grid in Hz
+    ///
+    /// Default - `None` (Nyquist frequency)
+    #[builder(default, setter(strip_option))]
+    pub max_frequency: Option<f64>,
+    /// Number of log-spaced frequencies in the grid
+    ///
+    /// Default - 256
+    #[builder(default = "256")]
+    pub num_points: usize,
+    /// Length of impulse response measured when the unit has no analytic response,
+    /// rounded up to a power of two in range `[2..=32768]`
+    ///
+    /// Default - 8192
+    #[builder(default = "8192")]
+    pub impulse_length: usize,
+}
+
+/// Configuration of Bode plot
+///
+/// Plots magnitude in dB and phase in degrees of every output's frequency response
+/// on a logarithmic frequency axis, see [crate::response::measure_frequency_response]
+#[derive(Debug, Clone, Builder)]
+pub struct BodeConfig {
+    /// Chart colors, titles and layout of outputs
+    ///
+    /// `svg_width` defaults to 1000, `with_inputs` and `format_x_axis_labels_as_time` are ignored
+    ///
+    /// Default - `SvgChartConfig::default()`
+    #[builder(default, setter(into))]
+    pub chart: SvgChartConfig,
+    /// Frequency grid and measurement
+    ///
+    /// Default - `FrequencyResponseConfig::default()`
+    #[builder(default, setter(into))]
+    pub response: FrequencyResponseConfig,
+    /// Bottom of the magnitude axis in dB, lower magnitudes are clamped to it
+    ///
+    /// Default - -120.0
+    #[builder(default = "-120.0")]
+    pub min_db: f64,
+}
+
+/// Configuration of phase and group delay chart
+///
+/// Plots unwrapped phase in degrees and group delay in samples of every output's
+/// frequency response on a logarithmic frequency axis,
+/// see [crate::response::measure_frequency_response]
+#[derive(Debug, Clone, Default, Builder)]
+pub struct GroupDelayConfig {
+    /// Chart colors, titles and layout of outputs
+    ///
+    /// `svg_width` defaults to 1000, `with_inputs` and `format_x_axis_labels_as_time` are ignored
+    ///
+    /// Default - `SvgChartConfig::default()`
+    #[builder(default, setter(into))]
+    pub chart: SvgChartConfig,
+    /// Frequency grid and measurement
+    ///
+    /// Default - `FrequencyResponseConfig::default()`
+    #[builder(default, setter(into))]
+    pub response: FrequencyResponseConfig,
+}
+
+/// Configuration of textual sample dumps
+///
+/// Used by `SnapshotOutputMode::Csv` and `SnapshotOutputMode::Json`
+#[derive(Debug, Clone, Builder)]
+pub struct SampleDumpConfig {
+    /// Number of digits after the decimal point
+    ///
+    /// Default - 6
+    #[builder(default = "6")]
+    pub precision: usize,
+    /// Whether to include inputs in snapshot
+    ///
+    /// Default - `false`
+    #[builder(default)]
+    pub include_inputs: bool,
+    /// Whether to include sample rate, number of samples, warm-up and processing mode
+    ///
+    /// Default - `true`
+    #[builder(default = "true")]
+    pub include_metadata: bool,
+}
+
+/// Configuration of text waveform chart
+///
+/// Draws every channel as a fixed-width waveform of Unicode characters,
+/// small enough for inline snapshots
+#[derive(Debug, Clone, Builder)]
+pub struct TextChartConfig {
+    /// Chart title, channel titles and inputs
+    ///
+    /// Only `chart_title`, `output_titles`, `input_titles`, `with_inputs` and `show_labels` apply
+    ///
+    /// Default - `SvgChartConfig::default()`
+    #[builder(default, setter(into))]
+    pub chart: SvgChartConfig,
+    /// Width of each waveform in characters
+    ///
+    /// Default - 64
+    #[builder(default = "64")]
+    pub width: usize,
+    /// Height of each waveform in lines
+    ///
+    /// Default - 4
+    #[builder(default = "4")]
+    pub height: usize,
+    /// Characters used to draw waveforms
+    ///
+    /// Default - `TextChartStyle::Braille`
+    #[builder(default)]
+    pub style: TextChartStyle,
+}
+
+/// Configuration of waveshaper transfer curve
+///
+/// Sweeps a static input ramp through the unit and plots every output against the input,
+/// see [crate::snapshot::snapshot_transfer_curve]
+#[derive(Debug, Clone, Builder)]
+pub struct TransferCurveConfig {
+    /// Chart colors, titles and layout of outputs
+    ///
+    /// `with_inputs`, `format_x_axis_labels_as_time`, `y_scale` and `annotations` are ignored,
+    /// `YRange::Fixed` sets the output axis
+    ///
+    /// Default - `SvgChartConfig::default()` with `svg_width` equal to `svg_height_per_channel`
+    #[builder(default, setter(into))]
+    pub chart: SvgChartConfig,
+    /// Input at the start of the ramp
+    ///
+    /// Default - -2.0
+    #[builder(default = "-2.0")]
+    pub min_input: f32,
+    /// Input at the end of the ramp
+    ///
+    /// Default - 2.0
+    #[builder(default = "2.0")]
+    pub max_input: f32,
+    /// Number of ramp samples
+    ///
+    /// Default - 1024
+    #[builder(default = "1024")]
+    pub num_points: usize,
+    /// Whether to draw the identity line `output = input`
+    ///
+    /// Default - `true`
+    #[builder(default = "true")]
+    pub show_identity: bool,
+    /// Whether to draw the slope of each curve, `d output / d input`, on a secondary axis
+    ///
+    /// Default - `false`
+    #[builder(default)]
+    pub show_derivative: bool,
+}
+
+/// Configuration of harmonic distortion analysis
+///
+/// Units with inputs are driven by [InputSource::sine](crate::input::InputSource::sine)
+/// at `frequency` on every input, units without inputs are analysed free-running,
+/// see [crate::snapshot::snapshot_harmonics] and [crate::harmonics::HarmonicAnalysis]
+#[derive(Debug, Clone, Builder)]
+pub struct HarmonicsConfig {
+    /// Chart colors, titles and layout of outputs
+    ///
+    /// `svg_width` defaults to 1000, `with_inputs` and `format_x_axis_labels_as_time` are ignored
+    ///
+    /// Default - `SvgChartConfig::default()`
+    #[builder(default, setter(into))]
+    pub chart: SvgChartConfig,
+    /// Frequency of the drive sine in Hz, or the expected fundamental of a free-running oscillator
+    ///
+    /// Default - 1000.0
+    #[builder(default = "1000.0")]
+    pub frequency: f64,
+    /// Highest analysed harmonic number, harmonics above Nyquist are omitted
+    ///
+    /// Default - 10
+    #[builder(default = "10")]
+    pub num_harmonics: usize,
+    /// Window function applied to each FFT frame
+    ///
+    /// Default - `WindowFunction::BlackmanHarris`
+    #[builder(default = "WindowFunction::BlackmanHarris")]
+    pub window: WindowFunction,
+    /// Number of analysed samples, longer than 32768 are averaged over 50% overlapping frames
+    ///
+    /// Default - 16384
+    #[builder(default = "16384")]
+    pub num_samples: usize,
+    /// Sample rate the unit is processed at
+    ///
+    /// Default - 44100.0 [fundsp::DEFAULT_SR]
+    #[builder(default = "fundsp::DEFAULT_SR")]
+    pub sample_rate: f64,
+    /// Warm-up before analysed samples, lets filters and DC blockers settle
+    ///
+    /// Default - `WarmUp::None`
+    #[builder(default = "WarmUp::None")]
+    pub warm_up: WarmUp,
+    /// Bottom of the level axis in dBFS, quieter harmonics are clamped to it
+    ///
+    /// Default - -140.0
+    #[builder(default = "-140.0")]
+    pub min_db: f64,
+}
+
+/// Configuration of aliasing sweep
+///
+/// Measures non-harmonic energy of every output at logarithmically spaced fundamental
+/// frequencies. Units with inputs are driven by
+/// [InputSource::sine](crate::input::InputSource::sine) at each frequency,
+/// units without inputs are analysed free-running,
+/// see [crate::snapshot::snapshot_aliasing] and [crate::aliasing::AliasingSweep]
+#[derive(Debug, Clone, Builder)]
+pub struct AliasingConfig {
+    /// Chart colors, titles and layout of outputs
+    ///
+    /// `svg_width` defaults to 1000, `with_inputs` and `format_x_axis_labels_as_time` are ignored
+    ///
+    /// Default - `SvgChartConfig::default()`
+    #[builder(default, setter(into))]
+    pub chart: SvgChartConfig,
+    /// Lowest swept fundamental in Hz, raised to
+    /// [crate::aliasing::min_resolvable_frequency] of `window`, `num_samples` and `sample_rate`
+    ///
+    /// Default - 100.0
+    #[builder(default = "100.0")]
+    pub min_frequency: f64,
+    /// Highest swept fundamental in Hz, clamped to 90% of Nyquist
+    ///
+    /// Aliases of fundamentals dividing `sample_rate` (e.g. `sample_rate / 4`)
+    /// fold onto harmonics and go unnoticed, avoid them as sweep ends
+    ///
+    /// Default - 10000.0
+    #[builder(default = "10000.0")]
+    pub max_frequency: f64,
+    /// Number of swept frequencies
+    ///
+    /// Default - 16
+    #[builder(default = "16")]
+    pub num_frequencies: usize,
+    /// Highest allowed alias level in dBc, checked by [crate::snapshot::snapshot_aliasing]
+    /// and drawn as a reference line
+    ///
+    /// Default - `None`
+    #[builder(default, setter(strip_option))]
+    pub max_alias_level: Option<f64>,
+    /// Window function applied to each FFT frame
+    ///
+    /// Default - `WindowFunction::BlackmanHarris`
+    #[builder(default = "WindowFunction::BlackmanHarris")]
+    pub window: WindowFunction,
+    /// Number of analysed samples per frequency,
+    /// longer than 32768 are averaged over 50% overlapping frames
+    ///
+    /// Default - 16384
+    #[builder(default = "16384")]
+    pub num_samples: usize,
+    /// Sample rate units are processed at
+    ///
+    /// Default - 44100.0 [fundsp::DEFAULT_SR]
+    #[builder(default = "fundsp::DEFAULT_SR")]
+    pub sample_rate: f64,
+    /// Warm-up before analysed samples at every frequency
+    ///
+    /// Default - `WarmUp::None`
+    #[builder(default = "WarmUp::None")]
+    pub warm_up: WarmUp,
+    /// Bottom of the alias level axis in dBc, lower levels are clamped to it
+    ///
+    /// Default - -140.0
+    #[builder(default = "-140.0")]
+    pub min_db: f64,
+}
+
+/// Loudness and true-peak limits of captured outputs, see [crate::loudness::Loudness::check]
+///
+/// ```
+/// use insta_fun::prelude::*;
+///
+/// let limits = LoudnessLimitsBuilder::default()
+///     .target_integrated(-14.0)
+///     .max_true_peak(-1.0)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct LoudnessLimits {
+    /// Expected integrated loudness in LUFS
+    ///
+    /// Default - `None`
+    #[builder(default, setter(strip_option))]
+    pub target_integrated: Option<f64>,
+    /// Allowed deviation from `target_integrated` in LU
+    ///
+    /// Default - 1.0
+    #[builder(default = "1.0")]
+    pub integrated_tolerance: f64,
+    /// Highest short-term loudness in LUFS
+    ///
+    /// Default - `None`
+    #[builder(default, setter(strip_option))]
+    pub max_short_term: Option<f64>,
+    /// Highest momentary loudness in LUFS
+    ///
+    /// Default - `None`
+    #[builder(default, setter(strip_option))]
+    pub max_momentary: Option<f64>,
+    /// Highest true-peak in dBTP
+    ///
+    /// Default - `None`
+    #[builder(default, setter(strip_option))]
+    pub max_true_peak: Option<f64>,
+}
+
 #[derive(Debug, Clone)]
 pub enum WavOutput {
     Wav16,
@@ -308,10 +843,42 @@ pub enum WavOutput {
 pub enum SnapshotOutputMode {
     SvgChart(SvgChartConfig),
     Wav(WavOutput),
+    /// Lossless binary dump of captured samples
+    ///
+    /// Compared against the stored snapshot within tolerance
+    Raw(Tolerance),
+    /// Magnitude spectrum chart
+    Spectrum(SpectrumConfig),
+    /// Spectrogram (STFT) heat map chart
+    Spectrogram(SpectrogramConfig),
+    /// Bode plot of frequency response
+    Bode(BodeConfig),
+    /// Unwrapped phase and group delay chart of frequency response
+    GroupDelay(GroupDelayConfig),
+    /// Captured samples as CSV text, one row per sample
+    Csv(SampleDumpConfig),
+    /// Captured samples as JSON text, one sample per line
+    Json(SampleDumpConfig),
+    /// Text waveform chart
+    Text(TextChartConfig),
+    /// Self-contained interactive HTML viewer of captured samples
+    ///
+    /// Supports zoom, pan, per-channel toggling and a value readout at the cursor.
+    /// `svg_width` sets page width, `preserve_aspect_ratio`, `max_labels_x_axis`,
+    /// `series_style`, `decimation`, `y_scale`, `y_range`, `show_clipping`, `viewport`,
+    /// `inset`, `annotations`, `statistics` and `show_loudness` are ignored
+    Html(SvgChartConfig),
+    /// Waveform chart rendered as PNG, same layout as `SvgChart`
+    ///
+    /// `preserve_aspect_ratio` is ignored. Rendering fails with [SnapshotError::Png]
+    /// unless the `png` feature is enabled
+    ///
+    /// [SnapshotError::Png]: crate::error::SnapshotError::Png
+    Png(SvgChartConfig),
 }
 
 /// Processing mode for snapshotting an audio unit.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum Processing {
     #[default]
     /// Process one sample at a time.
@@ -343,6 +910,81 @@ impl From<SvgChartConfig> for SnapshotOutputMode {
     }
 }
 
+impl From<SpectrumConfig> for SnapshotOutputMode {
+    fn from(value: SpectrumConfig) -> Self {
+        SnapshotOutputMode::Spectrum(value)
+    }
+}
+
+impl From<SpectrogramConfig> for SnapshotOutputMode {
+    fn from(value: SpectrogramConfig) -> Self {
+        SnapshotOutputMode::Spectrogram(value)
+    }
+}
+
+impl TryFrom<SpectrogramConfigBuilder> for SnapshotOutputMode {
+    type Error = SpectrogramConfigBuilderError;
+
+    fn try_from(value: SpectrogramConfigBuilder) -> Result<Self, Self::Error> {
+        let inner = value.build()?;
+        Ok(SnapshotOutputMode::Spectrogram(inner))
+    }
+}
+
+impl From<BodeConfig> for SnapshotOutputMode {
+    fn from(value: BodeConfig) -> Self {
+        SnapshotOutputMode::Bode(value)
+    }
+}
+
+impl TryFrom<BodeConfigBuilder> for SnapshotOutputMode {
+    type Error = BodeConfigBuilderError;
+
+    fn try_from(value: BodeConfigBuilder) -> Result<Self, Self::Error> {
+        let inner = value.build()?;
+        Ok(SnapshotOutputMode::Bode(inner))
+    }
+}
+
+impl From<GroupDelayConfig> for SnapshotOutputMode {
+    fn from(value: GroupDelayConfig) -> Self {
+        SnapshotOutputMode::GroupDelay(value)
+    }
+}
+
+impl TryFrom<GroupDelayConfigBuilder> for SnapshotOutputMode {
+    type Error = GroupDelayConfigBuilderError;
+
+    fn try_from(value: GroupDelayConfigBuilder) -> Result<Self, Self::Error> {
+        let inner = value.build()?;
+        Ok(SnapshotOutputMode::GroupDelay(inner))
+    }
+}
+
+impl From<TextChartConfig> for SnapshotOutputMode {
+    fn from(value: TextChartConfig) -> Self {
+        SnapshotOutputMode::Text(value)
+    }
+}
+
+impl TryFrom<TextChartConfigBuilder> for SnapshotOutputMode {
+    type Error = TextChartConfigBuilderError;
+
+    fn try_from(value: TextChartConfigBuilder) -> Result<Self, Self::Error> {
+        let inner = value.build()?;
+        Ok(SnapshotOutputMode::Text(inner))
+    }
+}
+
+impl TryFrom<SpectrumConfigBuilder> for SnapshotOutputMode {
+    type Error = SpectrumConfigBuilderError;
+
+    fn try_from(value: SpectrumConfigBuilder) -> Result<Self, Self::Error> {
+        let inner = value.build()?;
+        Ok(SnapshotOutputMode::Spectrum(inner))
+    }
+}
+
 impl Default for SnapshotConfig {
     fn default() -> Self {
         Self {
@@ -351,7 +993,10 @@ impl Default for SnapshotConfig {
             processing_mode: Processing::default(),
             warm_up: WarmUp::default(),
             allow_abnormal_samples: false,
+            loudness_limits: None,
+            loudness_metadata: false,
             output_mode: SnapshotOutputMode::SvgChart(SvgChartConfig::default()),
+            extra_output_modes: Vec::new(),
         }
     }
 }
@@ -373,45 +1018,322 @@ impl Default for SvgChartConfig {
             input_colors: None,
             background_color: "#000000".to_string(),
             line_width: 2.0,
+            series_style: SeriesStyle::default(),
+            decimation: Decimation::default(),
+            y_scale: YScale::default(),
+            y_range: YRange::default(),
+            show_clipping: false,
+            viewport: None,
+            inset: None,
+            annotations: Vec::new(),
+            statistics: StatisticsDisplay::default(),
+            show_loudness: false,
+            path_precision: None,
+            path_simplification: None,
             chart_layout: Layout::default(),
             format_x_axis_labels_as_time: false,
         }
     }
 }
 
+impl Default for SpectrumConfig {
+    fn default() -> Self {
+        Self {
+            chart: SvgChartConfig::default(),
+            window: WindowFunction::default(),
+            fft_size: None,
+            min_frequency: 20.0,
+            max_frequency: None,
+            min_db: -120.0,
+        }
+    }
+}
+
+impl Default for SpectrogramConfig {
+    fn default() -> Self {
+        Self {
+            chart: SvgChartConfig::default(),
+            window: WindowFunction::default(),
+            fft_size: 512,
+            hop_size: None,
+            min_frequency: 20.0,
+            max_frequency: None,
+            min_db: -120.0,
+            color_map: ColorMap::default(),
+        }
+    }
+}
+
+impl Default for FrequencyResponseConfig {
+    fn default() -> Self {
+        Self {
+            min_frequency: 20.0,
+            max_frequency: None,
+            num_points: 256,
+            impulse_length: 8192,
+        }
+    }
+}
+
+impl FrequencyResponseConfig {
+    /// Log-spaced frequencies of the grid at `sample_rate`
+    pub fn frequencies(&self, sample_rate: f64) -> Vec<f64> {
+        let nyquist = sample_rate / 2.0;
+        let max_frequency = self.max_frequency.unwrap_or(nyquist).min(nyquist);
+        let min_frequency = self.min_frequency.clamp(f64::MIN_POSITIVE, max_frequency);
+        let num_points = self.num_points.max(2);
+        (0..num_points)
+            .map(|i| {
+                min_frequency
+                    * (max_frequency / min_frequency).powf(i as f64 / (num_points - 1) as f64)
+            })
+            .collect()
+    }
+}
+
+impl Default for BodeConfig {
+    fn default() -> Self {
+        Self {
+            chart: SvgChartConfig::default(),
+            response: FrequencyResponseConfig::default(),
+            min_db: -120.0,
+        }
+    }
+}
+
+impl Default for SampleDumpConfig {
+    fn default() -> Self {
+        Self {
+            precision: 6,
+            include_inputs: false,
+            include_metadata: true,
+        }
+    }
+}
+
+impl Default for TextChartConfig {
+    fn default() -> Self {
+        Self {
+            chart: SvgChartConfig::default(),
+            width: 64,
+            height: 4,
+            style: TextChartStyle::default(),
+        }
+    }
+}
+
+impl Default for LoudnessLimits {
+    fn default() -> Self {
+        Self {
+            target_integrated: None,
+            integrated_tolerance: 1.0,
+            max_short_term: None,
+            max_momentary: None,
+            max_true_peak: None,
+        }
+    }
+}
+
+impl Default for TransferCurveConfig {
+    fn default() -> Self {
+        Self {
+            chart: SvgChartConfig::default(),
+            min_input: -2.0,
+            max_input: 2.0,
+            num_points: 1024,
+            show_identity: true,
+            show_derivative: false,
+        }
+    }
+}
+
+impl Default for HarmonicsConfig {
+    fn default() -> Self {
+        Self {
+            chart: SvgChartConfig::default(),
+            frequency: 1000.0,
+            num_harmonics: 10,
+            window: WindowFunction::BlackmanHarris,
+            num_samples: 16384,
+            sample_rate: DEFAULT_SR,
+            warm_up: WarmUp::None,
+            min_db: -140.0,
+        }
+    }
+}
+
+impl Default for AliasingConfig {
+    fn default() -> Self {
+        Self {
+            chart: SvgChartConfig::default(),
+            min_frequency: 100.0,
+            max_frequency: 10000.0,
+            num_frequencies: 16,
+            max_alias_level: None,
+            window: WindowFunction::BlackmanHarris,
+            num_samples: 16384,
+            sample_rate: DEFAULT_SR,
+            warm_up: WarmUp::None,
+            min_db: -140.0,
+        }
+    }
+}
+
 impl SnapshotConfig {
+    /// All output modes: `output_mode` followed by `extra_output_modes`
+    pub fn output_modes(&self) -> impl Iterator<Item = &SnapshotOutputMode> {
+        std::iter::once(&self.output_mode).chain(self.extra_output_modes.iter())
+    }
+
     /// Intnded for internal use only
     ///
     /// Used by macros to determine snapshot filename
     pub fn file_name(&self, name: Option<&'_ str>) -> String {
-        match &self.output_mode {
-            SnapshotOutputMode::SvgChart(svg_chart_config) => match name {
-                Some(name) => format!("{name}.svg"),
-                None => match &svg_chart_config.chart_title {
-                    Some(name) => format!("{name}.svg"),
-                    None => ".svg".to_string(),
-                },
-            },
-            SnapshotOutputMode::Wav(_) => match name {
-                Some(name) => format!("{name}.wav"),
-                None => ".wav".to_string(),
-            },
-        }
+        self.output_mode.file_name(name)
     }
 
     /// Intnded for internal use only
     ///
     /// Used by macros to set chart title if not already set
     pub fn maybe_title(&mut self, name: &str) {
-        if matches!(
-            self.output_mode,
-            SnapshotOutputMode::SvgChart(SvgChartConfig {
-                chart_title: None,
-                ..
-            })
-        ) && let SnapshotOutputMode::SvgChart(ref mut svg_chart_config) = self.output_mode
-        {
-            svg_chart_config.chart_title = Some(name.to_string());
+        let modes =
+            std::iter::once(&mut self.output_mode).chain(self.extra_output_modes.iter_mut());
+        for svg_chart_config in modes.filter_map(SnapshotOutputMode::chart_config_mut) {
+            if svg_chart_config.chart_title.is_none() {
+                svg_chart_config.chart_title = Some(name.to_string());
+            }
+        }
+    }
+
+    /// Intended for internal use only
+    ///
+    /// Used by macros to describe snapshots of captured `data`
+    pub fn snapshot_description(&self, data: &SnapshotData) -> Option<String> {
+        self.loudness_metadata
+            .then(|| format!("Loudness: {}", data.loudness().summary()))
+    }
+}
+
+impl SnapshotOutputMode {
+    /// Intended for internal use only
+    ///
+    /// Used by macros to determine snapshot filename
+    pub fn file_name(&self, name: Option<&'_ str>) -> String {
+        let name = name.or_else(|| {
+            self.chart_config()
+                .and_then(|chart| chart.chart_title.as_deref())
+        });
+        format!("{}.{}", name.unwrap_or_default(), self.extension())
+    }
+
+    /// File extension of rendered snapshot
+    fn extension(&self) -> &'static str {
+        match self {
+            SnapshotOutputMode::SvgChart(_)
+            | SnapshotOutputMode::Spectrum(_)
+            | SnapshotOutputMode::Spectrogram(_)
+            | SnapshotOutputMode::Bode(_)
+            | SnapshotOutputMode::GroupDelay(_) => "svg",
+            SnapshotOutputMode::Wav(_) => "wav",
+            SnapshotOutputMode::Raw(_) => "raw",
+            SnapshotOutputMode::Csv(_) => "csv",
+            SnapshotOutputMode::Json(_) => "json",
+            SnapshotOutputMode::Text(_) => "txt",
+            SnapshotOutputMode::Html(_) => "html",
+            SnapshotOutputMode::Png(_) => "png",
+        }
+    }
+
+    /// Intended for internal use only
+    ///
+    /// Used by macros to keep snapshots of different output modes with the same name apart
+    pub fn snapshot_suffix(&self) -> Option<&'static str> {
+        match self {
+            SnapshotOutputMode::SvgChart(_) => None,
+            SnapshotOutputMode::Wav(_) => Some("audio"),
+            SnapshotOutputMode::Raw(_) => Some("raw"),
+            SnapshotOutputMode::Spectrum(_) => Some("spectrum"),
+            SnapshotOutputMode::Spectrogram(_) => Some("spectrogram"),
+            SnapshotOutputMode::Bode(_) => Some("bode"),
+            SnapshotOutputMode::GroupDelay(_) => Some("group_delay"),
+            SnapshotOutputMode::Csv(_) => Some("csv"),
+            SnapshotOutputMode::Json(_) => Some("json"),
+            SnapshotOutputMode::Text(_) => Some("text"),
+            SnapshotOutputMode::Html(_) => Some("html"),
+            SnapshotOutputMode::Png(_) => Some("png"),
+        }
+    }
+
+    /// Intended for internal use only
+    ///
+    /// Used by macros to store text snapshots inline in `.snap` files, so they diff line by line
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            SnapshotOutputMode::Csv(_) | SnapshotOutputMode::Json(_) | SnapshotOutputMode::Text(_)
+        )
+    }
+
+    /// Intended for internal use only
+    ///
+    /// Used during capture to measure frequency responses plotted by this output mode
+    pub fn frequency_response_config(&self) -> Option<&FrequencyResponseConfig> {
+        match self {
+            SnapshotOutputMode::Bode(bode_config) => Some(&bode_config.response),
+            SnapshotOutputMode::GroupDelay(group_delay_config) => {
+                Some(&group_delay_config.response)
+            }
+            _ => None,
+        }
+    }
+
+    /// Chart styling of chart output modes
+    fn chart_config(&self) -> Option<&SvgChartConfig> {
+        match self {
+            SnapshotOutputMode::SvgChart(svg_chart_config) => Some(svg_chart_config),
+            SnapshotOutputMode::Spectrum(spectrum_config) => Some(&spectrum_config.chart),
+            SnapshotOutputMode::Spectrogram(spectrogram_config) => Some(&spectrogram_config.chart),
+            SnapshotOutputMode::Bode(bode_config) => Some(&bode_config.chart),
+            SnapshotOutputMode::GroupDelay(group_delay_config) => Some(&group_delay_config.chart),
+            SnapshotOutputMode::Text(text_chart_config) => Some(&text_chart_config.chart),
+            SnapshotOutputMode::Html(html_chart_config) => Some(html_chart_config),
+            SnapshotOutputMode::Png(png_chart_config) => Some(png_chart_config),
+            SnapshotOutputMode::Wav(_)
+            | SnapshotOutputMode::Raw(_)
+            | SnapshotOutputMode::Csv(_)
+            | SnapshotOutputMode::Json(_) => None,
+        }
+    }
+
+    /// Chart styling of chart output modes
+    fn chart_config_mut(&mut self) -> Option<&mut SvgChartConfig> {
+        match self {
+            SnapshotOutputMode::SvgChart(svg_chart_config) => Some(svg_chart_config),
+            SnapshotOutputMode::Spectrum(spectrum_config) => Some(&mut spectrum_config.chart),
+            SnapshotOutputMode::Spectrogram(spectrogram_config) => {
+                Some(&mut spectrogram_config.chart)
+            }
+            SnapshotOutputMode::Bode(bode_config) => Some(&mut bode_config.chart),
+            SnapshotOutputMode::GroupDelay(group_delay_config) => {
+                Some(&mut group_delay_config.chart)
+            }
+            SnapshotOutputMode::Text(text_chart_config) => Some(&mut text_chart_config.chart),
+            SnapshotOutputMode::Html(html_chart_config) => Some(html_chart_config),
+            SnapshotOutputMode::Png(png_chart_config) => Some(png_chart_config),
+            SnapshotOutputMode::Wav(_)
+            | SnapshotOutputMode::Raw(_)
+            | SnapshotOutputMode::Csv(_)
+            | SnapshotOutputMode::Json(_) => None,
+        }
+    }
+
+    /// Intended for internal use only
+    ///
+    /// Used by macros to compare snapshots within tolerance instead of byte by byte
+    pub fn tolerance(&self) -> Option<Tolerance> {
+        match self {
+            SnapshotOutputMode::Raw(tolerance) => Some(*tolerance),
+            _ => None,
         }
     }
 }
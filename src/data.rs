@@ -0,0 +1,281 @@
+use crate::abnormal::AbnormalSample;
+#[cfg(feature = "png")]
+use crate::chart::generate_png;
+use crate::chart::generate_svg;
+use crate::config::{
+    BodeConfig, FrequencyResponseConfig, GroupDelayConfig, HarmonicsConfig, Processing,
+    SampleDumpConfig, SnapshotOutputMode, SpectrogramConfig, SpectrumConfig, SvgChartConfig,
+    TextChartConfig, TransferCurveConfig, WavOutput,
+};
+use crate::error::SnapshotError;
+use crate::harmonics::{HarmonicAnalysis, generate_harmonics_svg};
+use crate::html::generate_html;
+use crate::loudness::Loudness;
+use crate::raw::{decode_raw, encode_raw};
+use crate::response::FrequencyResponse;
+use crate::response_chart::{generate_bode_svg, generate_group_delay_svg};
+use crate::spectrogram::generate_spectrogram_svg;
+use crate::spectrum::generate_spectrum_svg;
+use crate::statistics::ChannelStatistics;
+use crate::text::{encode_csv, encode_json};
+use crate::text_chart::generate_text_chart;
+use crate::transfer_curve::generate_transfer_curve_svg;
+use crate::wav::generate_wav;
+
+/// Raw signal data captured from one processing run of an audio unit
+///
+/// Produced by [crate::snapshot::capture_audio_unit],
+/// rendered into snapshot bytes with [SnapshotData::render]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotData {
+    /// Input samples fed to the unit
+    ///
+    /// - First vec contains all **channels**
+    /// - Second vec contains **samples** per channel
+    pub inputs: Vec<Vec<f32>>,
+    /// Output samples produced by the unit
+    ///
+    /// Abnormal samples are replaced with `0.0`, see `abnormalities`
+    pub outputs: Vec<Vec<f32>>,
+    /// Abnormal samples per output channel as `(sample index, kind)`
+    pub abnormalities: Vec<Vec<(usize, AbnormalSample)>>,
+    /// Number of captured samples per channel
+    pub num_samples: usize,
+    /// Sample rate the unit was processed at
+    pub sample_rate: f64,
+    /// Number of warm-up samples processed before capturing
+    ///
+    /// Used as offset of the X axis in charts
+    pub start_sample: usize,
+    /// Processing mode used to capture outputs
+    pub processing_mode: Processing,
+    /// Frequency responses measured for output modes which plot them
+    ///
+    /// Not stored by `SnapshotOutputMode::Raw`
+    pub frequency_responses: Vec<FrequencyResponse>,
+}
+
+impl SnapshotData {
+    /// Statistics of every output channel
+    pub fn output_statistics(&self) -> Vec<ChannelStatistics> {
+        self.outputs
+            .iter()
+            .map(|samples| ChannelStatistics::from_samples(samples))
+            .collect()
+    }
+
+    /// BS.1770 loudness and true-peak of output channels
+    pub fn loudness(&self) -> Loudness {
+        Loudness::measure(&self.outputs, self.sample_rate)
+    }
+
+    /// Harmonic distortion of every output channel
+    ///
+    /// Meant for data captured from a sine or a free-running oscillator,
+    /// see [crate::snapshot::analyze_harmonics]
+    pub fn harmonic_analysis(&self, config: &HarmonicsConfig) -> Vec<HarmonicAnalysis> {
+        self.outputs
+            .iter()
+            .map(|samples| HarmonicAnalysis::measure(samples, self.sample_rate, config))
+            .collect()
+    }
+
+    /// Statistics of every input channel
+    pub fn input_statistics(&self) -> Vec<ChannelStatistics> {
+        self.inputs
+            .iter()
+            .map(|samples| ChannelStatistics::from_samples(samples))
+            .collect()
+    }
+
+    /// Render SVG chart of captured data
+    pub fn render_svg(&self, config: &SvgChartConfig) -> Vec<u8> {
+        generate_svg(self, config).into_bytes()
+    }
+
+    /// Render transfer curve of captured data, every output plotted against the first input
+    ///
+    /// Meant for data captured from a ramp, see [crate::snapshot::snapshot_transfer_curve]
+    pub fn render_transfer_curve(&self, config: &TransferCurveConfig) -> Vec<u8> {
+        generate_transfer_curve_svg(self, config).into_bytes()
+    }
+
+    /// Render harmonic bar chart of every output with THD and THD+N
+    ///
+    /// Meant for data captured from a sine or a free-running oscillator,
+    /// see [crate::snapshot::snapshot_harmonics]
+    pub fn render_harmonics(&self, config: &HarmonicsConfig) -> Vec<u8> {
+        generate_harmonics_svg(self, config).into_bytes()
+    }
+
+    /// Render self-contained interactive HTML viewer of captured data
+    ///
+    /// Embeds every captured sample, so long snapshots can be zoomed into
+    /// without re-running them
+    pub fn render_html(&self, config: &SvgChartConfig) -> Vec<u8> {
+        generate_html(self, config).into_bytes()
+    }
+
+    /// Render PNG chart of captured data, same layout as [SnapshotData::render_svg]
+    ///
+    /// Text uses an embedded font, so output doesn't depend on installed fonts
+    ///
+    /// Panics if PNG data can not be rendered, see [SnapshotData::try_render_png]
+    #[cfg(feature = "png")]
+    pub fn render_png(&self, config: &SvgChartConfig) -> Vec<u8> {
+        self.try_render_png(config)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Render PNG chart of captured data, same layout as [SnapshotData::render_svg]
+    #[cfg(feature = "png")]
+    pub fn try_render_png(&self, config: &SvgChartConfig) -> Result<Vec<u8>, SnapshotError> {
+        generate_png(self, config)
+    }
+
+    /// Render magnitude spectrum chart of captured data
+    pub fn render_spectrum(&self, config: &SpectrumConfig) -> Vec<u8> {
+        generate_spectrum_svg(self, config).into_bytes()
+    }
+
+    /// Render spectrogram heat map of captured data
+    pub fn render_spectrogram(&self, config: &SpectrogramConfig) -> Vec<u8> {
+        generate_spectrogram_svg(self, config).into_bytes()
+    }
+
+    /// Render Bode plot of captured frequency response
+    ///
+    /// Panics when response for `config.response` was not captured, see [SnapshotData::try_render_bode]
+    pub fn render_bode(&self, config: &BodeConfig) -> Vec<u8> {
+        self.try_render_bode(config)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Render Bode plot of captured frequency response
+    pub fn try_render_bode(&self, config: &BodeConfig) -> Result<Vec<u8>, SnapshotError> {
+        let response = self.frequency_response(&config.response)?;
+        Ok(generate_bode_svg(response, config).into_bytes())
+    }
+
+    /// Render unwrapped phase and group delay chart of captured frequency response
+    ///
+    /// Panics when response for `config.response` was not captured,
+    /// see [SnapshotData::try_render_group_delay]
+    pub fn render_group_delay(&self, config: &GroupDelayConfig) -> Vec<u8> {
+        self.try_render_group_delay(config)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Render unwrapped phase and group delay chart of captured frequency response
+    pub fn try_render_group_delay(
+        &self,
+        config: &GroupDelayConfig,
+    ) -> Result<Vec<u8>, SnapshotError> {
+        let response = self.frequency_response(&config.response)?;
+        Ok(generate_group_delay_svg(response, config).into_bytes())
+    }
+
+    /// Captured frequency response measured with `config`
+    pub fn frequency_response(
+        &self,
+        config: &FrequencyResponseConfig,
+    ) -> Result<&FrequencyResponse, SnapshotError> {
+        self.frequency_responses
+            .iter()
+            .find(|response| response.config == *config)
+            .ok_or(SnapshotError::MissingFrequencyResponse)
+    }
+
+    /// Render WAV file of captured outputs
+    ///
+    /// Panics if WAV data can not be written, see [SnapshotData::try_render_wav]
+    pub fn render_wav(&self, output: &WavOutput) -> Vec<u8> {
+        self.try_render_wav(output)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Render WAV file of captured outputs
+    pub fn try_render_wav(&self, output: &WavOutput) -> Result<Vec<u8>, SnapshotError> {
+        generate_wav(&self.outputs, output, self.sample_rate, self.num_samples)
+    }
+
+    /// Encode captured data losslessly, as stored by `SnapshotOutputMode::Raw`
+    pub fn to_raw(&self) -> Vec<u8> {
+        encode_raw(self)
+    }
+
+    /// Dump captured samples as CSV, as stored by `SnapshotOutputMode::Csv`
+    pub fn to_csv(&self, config: &SampleDumpConfig) -> String {
+        encode_csv(self, config)
+    }
+
+    /// Dump captured samples as JSON, as stored by `SnapshotOutputMode::Json`
+    pub fn to_json(&self, config: &SampleDumpConfig) -> String {
+        encode_json(self, config)
+    }
+
+    /// Render channels as text waveforms, as stored by `SnapshotOutputMode::Text`
+    ///
+    /// Small enough for inline snapshots
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use insta_fun::prelude::*;
+    /// use fundsp::prelude::*;
+    ///
+    /// let config = SnapshotConfigBuilder::default().num_samples(100).build().unwrap();
+    /// let data = capture_audio_unit(dc(1.0), InputSource::None, &config);
+    /// let text = data.render_text(&TextChartConfigBuilder::default().width(8).height(1).build().unwrap());
+    ///
+    /// assert_eq!(text, "Output Ch#0 [1.000, 1.000]\n⠉⠉⠉⠉⠉⠉⠉⠉\n");
+    /// ```
+    pub fn render_text(&self, config: &TextChartConfig) -> String {
+        generate_text_chart(self, config)
+    }
+
+    /// Decode data produced by [SnapshotData::to_raw]
+    pub fn from_raw(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        decode_raw(bytes)
+    }
+
+    /// Render captured data using given output mode
+    ///
+    /// Panics on any [SnapshotError], see [SnapshotData::try_render]
+    pub fn render(&self, output_mode: &SnapshotOutputMode) -> Vec<u8> {
+        self.try_render(output_mode)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Render captured data using given output mode
+    pub fn try_render(&self, output_mode: &SnapshotOutputMode) -> Result<Vec<u8>, SnapshotError> {
+        match output_mode {
+            SnapshotOutputMode::SvgChart(svg_chart_config) => Ok(self.render_svg(svg_chart_config)),
+            SnapshotOutputMode::Wav(wav_output) => self.try_render_wav(wav_output),
+            SnapshotOutputMode::Raw(_) => Ok(self.to_raw()),
+            SnapshotOutputMode::Csv(dump_config) => Ok(self.to_csv(dump_config).into_bytes()),
+            SnapshotOutputMode::Json(dump_config) => Ok(self.to_json(dump_config).into_bytes()),
+            SnapshotOutputMode::Text(text_chart_config) => {
+                Ok(self.render_text(text_chart_config).into_bytes())
+            }
+            SnapshotOutputMode::Html(html_chart_config) => Ok(self.render_html(html_chart_config)),
+            SnapshotOutputMode::Spectrum(spectrum_config) => {
+                Ok(self.render_spectrum(spectrum_config))
+            }
+            SnapshotOutputMode::Spectrogram(spectrogram_config) => {
+                Ok(self.render_spectrogram(spectrogram_config))
+            }
+            SnapshotOutputMode::Bode(bode_config) => self.try_render_bode(bode_config),
+            SnapshotOutputMode::GroupDelay(group_delay_config) => {
+                self.try_render_group_delay(group_delay_config)
+            }
+            #[cfg(feature = "png")]
+            SnapshotOutputMode::Png(png_chart_config) => self.try_render_png(png_chart_config),
+            #[cfg(not(feature = "png"))]
+            SnapshotOutputMode::Png(_) => Err(SnapshotError::Png(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "insta-fun was built without the `png` feature",
+            ))),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+/// Reduction of waveform samples to plotted points
+///
+/// Applies when a chart has more samples than pixel columns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Decimation {
+    /// One path vertex per sample
+    ///
+    /// Default SVG width stays proportional to `num_samples`
+    #[default]
+    Exact,
+    /// Minimum and maximum of the samples in each pixel column
+    MinMax,
+    /// Same as `MinMax`, with RMS envelope of each pixel column drawn on top
+    MinMaxRms,
+}
+
+/// Maximum default SVG width when decimating
+pub(crate) const MAX_DEFAULT_WIDTH: usize = 4000;
+
+impl Decimation {
+    /// Default SVG width for `num_samples`
+    pub(crate) fn default_width(&self, num_samples: usize) -> usize {
+        match self {
+            Decimation::Exact => num_samples * 2,
+            Decimation::MinMax | Decimation::MinMaxRms => (num_samples * 2).min(MAX_DEFAULT_WIDTH),
+        }
+    }
+
+    /// Whether `num_samples` are reduced to fit `columns` pixel columns
+    pub(crate) fn applies(&self, num_samples: usize, columns: usize) -> bool {
+        *self != Decimation::Exact && num_samples > columns.max(1)
+    }
+}
+
+/// Sample range of each of `columns` pixel columns
+fn column_ranges(
+    num_samples: usize,
+    columns: usize,
+) -> impl Iterator<Item = std::ops::Range<usize>> {
+    (0..columns)
+        .map(move |column| column * num_samples / columns..(column + 1) * num_samples / columns)
+}
+
+/// Waveform vertices as `(sample index, value)`
+///
+/// Keeps every sample unless decimation applies, otherwise
+/// the minimum and maximum of each pixel column in order of occurrence
+pub(crate) fn waveform_points(
+    data: &[f32],
+    decimation: Decimation,
+    columns: usize,
+) -> Vec<(usize, f32)> {
+    if !decimation.applies(data.len(), columns) {
+        return data.iter().cloned().enumerate().collect();
+    }
+
+    let mut points = Vec::with_capacity(columns * 2);
+    for range in column_ranges(data.len(), columns) {
+        let start = range.start;
+        let chunk = &data[range];
+        if chunk.is_empty() {
+            continue;
+        }
+
+        let (mut min_i, mut max_i) = (0, 0);
+        for (i, &sample) in chunk.iter().enumerate() {
+            if sample < chunk[min_i] {
+                min_i = i;
+            }
+            if sample > chunk[max_i] {
+                max_i = i;
+            }
+        }
+
+        let (first, second) = (min_i.min(max_i), min_i.max(max_i));
+        points.push((start + first, chunk[first]));
+        if second != first {
+            points.push((start + second, chunk[second]));
+        }
+    }
+    points
+}
+
+/// RMS of each pixel column as `(first sample index, last sample index, rms)`
+///
+/// Empty unless decimation applies
+pub(crate) fn rms_envelope(
+    data: &[f32],
+    decimation: Decimation,
+    columns: usize,
+) -> Vec<(usize, usize, f32)> {
+    if decimation != Decimation::MinMaxRms || !decimation.applies(data.len(), columns) {
+        return Vec::new();
+    }
+
+    column_ranges(data.len(), columns)
+        .filter(|range| !range.is_empty())
+        .map(|range| {
+            let count = range.len() as f32;
+            let (first, last) = (range.start, range.end - 1);
+            let sum_of_squares: f32 = data[range].iter().map(|v| v * v).sum();
+            (first, last, (sum_of_squares / count).sqrt())
+        })
+        .collect()
+}
@@ -0,0 +1,127 @@
+use fundsp::MAX_BUFFER_SIZE;
+
+pub use crate::abnormal::AbnormalSample;
+
+/// Error produced while snapshotting an audio unit
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// Output channel produced an abnormal sample (`NaN`, `±Infinity`)
+    /// while `allow_abnormal_samples` is `false`
+    AbnormalSample {
+        /// Output channel index
+        channel: usize,
+        /// Sample index (not counting warm-up)
+        sample: usize,
+        /// Kind of abnormal sample
+        kind: AbnormalSample,
+    },
+    /// Input source provides a different number of channels than the unit has inputs
+    InputChannelMismatch {
+        /// Number of unit inputs
+        expected: usize,
+        /// Number of channels provided by the input source
+        actual: usize,
+    },
+    /// Input source provides a different number of samples than requested
+    InputLengthMismatch {
+        /// Requested number of samples
+        expected: usize,
+        /// Number of samples provided by the input source
+        actual: usize,
+    },
+    /// `Processing::Batch` size is zero or exceeds [fundsp::MAX_BUFFER_SIZE]
+    InvalidBatchSize { batch_size: u8 },
+    /// Writing WAV data failed
+    Wav(std::io::Error),
+    /// Rendering PNG data failed, or the `png` feature is disabled
+    Png(std::io::Error),
+    /// Raw snapshot data can not be decoded
+    InvalidRawData(String),
+    /// Output mode plots a frequency response which wasn't captured
+    MissingFrequencyResponse,
+    /// Loudness or true-peak of captured outputs exceeds `loudness_limits`
+    LoudnessLimitExceeded {
+        /// Exceeded metric, e.g. "true peak"
+        metric: &'static str,
+        /// Measured value in LUFS or dBTP
+        value: f64,
+        /// Exceeded limit in LUFS or dBTP
+        limit: f64,
+    },
+    /// Alias level of an output exceeds `max_alias_level` of the aliasing sweep
+    AliasingLimitExceeded {
+        /// Output channel index
+        channel: usize,
+        /// Swept fundamental frequency in Hz
+        frequency: f64,
+        /// Measured alias level in dBc
+        level: f64,
+        /// Exceeded limit in dBc
+        limit: f64,
+    },
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::AbnormalSample {
+                channel,
+                sample,
+                kind,
+            } => write!(
+                f,
+                "Output channel #[{channel}] at sample [{sample}] produced [{kind}] sample"
+            ),
+            SnapshotError::InputChannelMismatch { expected, actual } => write!(
+                f,
+                "Input channel count mismatch. Expected {expected} channels, got {actual}"
+            ),
+            SnapshotError::InputLengthMismatch { expected, actual } => write!(
+                f,
+                "Input length mismatch. Expected {expected} samples per channel, got {actual}"
+            ),
+            SnapshotError::InvalidBatchSize { batch_size } => write!(
+                f,
+                "Batch size must be in range [1..={MAX_BUFFER_SIZE}], got [{batch_size}]"
+            ),
+            SnapshotError::Wav(err) => write!(f, "Failed to write .wav snapshot data: {err}"),
+            SnapshotError::Png(err) => write!(f, "Failed to render .png snapshot: {err}"),
+            SnapshotError::InvalidRawData(reason) => {
+                write!(f, "Invalid .raw snapshot data: {reason}")
+            }
+            SnapshotError::MissingFrequencyResponse => write!(
+                f,
+                "Frequency response was not captured, include the output mode in SnapshotConfig before capturing"
+            ),
+            SnapshotError::LoudnessLimitExceeded {
+                metric,
+                value,
+                limit,
+            } => write!(f, "Output {metric} [{value:.2}] exceeds limit [{limit:.2}]"),
+            SnapshotError::AliasingLimitExceeded {
+                channel,
+                frequency,
+                level,
+                limit,
+            } => write!(
+                f,
+                "Output channel #[{channel}] alias level at [{frequency:.1}] Hz [{level:.2}] dBc exceeds limit [{limit:.2}] dBc"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Wav(err) | SnapshotError::Png(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(err: std::io::Error) -> Self {
+        SnapshotError::Wav(err)
+    }
+}
@@ -0,0 +1,426 @@
+use plotters::backend::SVGBackend;
+use plotters::drawing::IntoDrawingArea;
+use plotters::prelude::*;
+
+use crate::chart::{finish_svg, group_axis_color, layout_groups};
+use crate::chart_data::ChannelChartData;
+use crate::config::HarmonicsConfig;
+use crate::data::SnapshotData;
+use crate::spectrum::{WindowFunction, averaged_magnitudes, fft_size};
+use crate::util::{get_contrasting_color, parse_hex_color};
+
+const DEFAULT_WIDTH: usize = 1000;
+/// Smallest power summed into levels, avoids `-inf` dB
+const MIN_POWER: f64 = 1e-30;
+
+/// Level of one harmonic of the analysed signal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Harmonic {
+    /// Harmonic number, `1` is the fundamental
+    pub number: usize,
+    /// Frequency of the spectral peak in Hz
+    pub frequency: f64,
+    /// Level in dBFS, a full scale sine reads 0 dB
+    pub level: f64,
+    /// Level relative to the fundamental in dBc
+    pub relative_level: f64,
+}
+
+/// Total harmonic distortion (THD), THD plus noise (THD+N) and harmonic levels
+/// of one captured channel
+///
+/// Levels are measured from a windowed FFT by summing the power of the main lobe
+/// around each harmonic, so they don't depend on where a harmonic falls between bins.
+/// Harmonics above Nyquist are omitted.
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let config = HarmonicsConfigBuilder::default()
+///     .frequency(1000.0)
+///     .build()
+///     .unwrap();
+/// let analysis = &analyze_harmonics(shape(Tanh(4.0)), &config)[0];
+/// // Odd symmetric saturation adds odd harmonics only
+/// assert!(analysis.harmonic(3).unwrap().relative_level > -30.0);
+/// assert!(analysis.harmonic(2).unwrap().relative_level < -80.0);
+/// assert!(analysis.thd_db() > -20.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarmonicAnalysis {
+    /// Strongest peak near the configured frequency
+    pub fundamental: Harmonic,
+    /// Harmonics from the 2nd up to `num_harmonics`
+    pub harmonics: Vec<Harmonic>,
+    /// Ratio of the RMS of all harmonics to the fundamental
+    pub thd: f64,
+    /// Ratio of the RMS of everything but the fundamental and DC to the fundamental
+    pub thd_n: f64,
+}
+
+impl HarmonicAnalysis {
+    /// Analyse `samples` sampled at `sample_rate`
+    ///
+    /// Fundamental is searched within the main lobe of the window around `config.frequency`
+    pub fn measure(samples: &[f32], sample_rate: f64, config: &HarmonicsConfig) -> Self {
+        let spectrum = LobeSpectrum::new(samples, sample_rate, config.window);
+
+        let fundamental_bin = spectrum.peak_near(config.frequency);
+        let fundamental_power = spectrum.lobe_power(fundamental_bin).max(MIN_POWER);
+        let fundamental_frequency = spectrum.peak_frequency(fundamental_bin);
+        let fundamental = Harmonic {
+            number: 1,
+            frequency: fundamental_frequency,
+            level: spectrum.level(fundamental_power),
+            relative_level: 0.0,
+        };
+
+        let nyquist = sample_rate / 2.0;
+        let (harmonics, harmonic_powers): (Vec<Harmonic>, Vec<f64>) = (2..=config.num_harmonics)
+            .map(|number| (number, fundamental_frequency * number as f64))
+            .take_while(|&(_, frequency)| frequency < nyquist)
+            .map(|(number, frequency)| {
+                let bin = spectrum.peak_near(frequency);
+                let harmonic_power = spectrum.lobe_power(bin);
+                let harmonic = Harmonic {
+                    number,
+                    frequency: spectrum.peak_frequency(bin),
+                    level: spectrum.level(harmonic_power),
+                    relative_level: relative_db(harmonic_power, fundamental_power),
+                };
+                (harmonic, harmonic_power)
+            })
+            .unzip();
+
+        let fundamental_lobe = spectrum.lobe(fundamental_bin);
+        let noise_and_distortion =
+            spectrum.power_excluding(|bin| spectrum.is_dc(bin) || fundamental_lobe.contains(&bin));
+
+        Self {
+            fundamental,
+            harmonics,
+            thd: (harmonic_powers.iter().sum::<f64>() / fundamental_power).sqrt(),
+            thd_n: (noise_and_distortion / fundamental_power).sqrt(),
+        }
+    }
+
+    /// Harmonic by number, `1` is the fundamental
+    pub fn harmonic(&self, number: usize) -> Option<&Harmonic> {
+        std::iter::once(&self.fundamental)
+            .chain(&self.harmonics)
+            .find(|harmonic| harmonic.number == number)
+    }
+
+    /// THD in percent
+    pub fn thd_percent(&self) -> f64 {
+        self.thd * 100.0
+    }
+
+    /// THD in dB relative to the fundamental
+    pub fn thd_db(&self) -> f64 {
+        20.0 * self.thd.max(f64::MIN_POSITIVE).log10()
+    }
+
+    /// THD+N in percent
+    pub fn thd_n_percent(&self) -> f64 {
+        self.thd_n * 100.0
+    }
+
+    /// THD+N in dB relative to the fundamental
+    pub fn thd_n_db(&self) -> f64 {
+        20.0 * self.thd_n.max(f64::MIN_POSITIVE).log10()
+    }
+}
+
+/// Level of `power` relative to `reference` in dB
+pub(crate) fn relative_db(power: f64, reference: f64) -> f64 {
+    10.0 * (power / reference.max(MIN_POWER)).max(MIN_POWER).log10()
+}
+
+/// Half width in bins of the main lobe of `window`, beyond which leakage is negligible
+pub(crate) fn main_lobe_half_width(window: WindowFunction) -> usize {
+    match window {
+        WindowFunction::Rectangular => 1,
+        WindowFunction::Hann | WindowFunction::Hamming => 2,
+        WindowFunction::Blackman => 3,
+        WindowFunction::BlackmanHarris => 4,
+    }
+}
+
+/// Windowed power spectrum measuring sine components by the power of their main lobe
+pub(crate) struct LobeSpectrum {
+    /// Power per bin, a full scale sine peaks at `1.0`
+    power: Vec<f64>,
+    fft_size: usize,
+    sample_rate: f64,
+    /// Main lobe half width in bins
+    half_width: usize,
+    /// Equivalent noise bandwidth in bins, converts lobe power to sine power
+    enbw: f64,
+}
+
+impl LobeSpectrum {
+    pub(crate) fn new(samples: &[f32], sample_rate: f64, window: WindowFunction) -> Self {
+        let fft_size = fft_size(None, samples.len());
+        let frame_size = samples.len().min(fft_size);
+        let power = averaged_magnitudes(samples, fft_size, window)
+            .into_iter()
+            .map(|m| m as f64 * m as f64)
+            .collect();
+
+        let (sum, sum_of_squares) = window
+            .coefficients(frame_size)
+            .iter()
+            .fold((0.0_f64, 0.0_f64), |(sum, squares), &w| {
+                (sum + w as f64, squares + w as f64 * w as f64)
+            });
+
+        Self {
+            power,
+            fft_size,
+            sample_rate,
+            // Main lobe widens when short frames are zero-padded
+            half_width: (main_lobe_half_width(window) * fft_size).div_ceil(frame_size.max(1)),
+            enbw: (fft_size as f64 * sum_of_squares / (sum * sum).max(f64::MIN_POSITIVE)).max(1.0),
+        }
+    }
+
+    /// Highest bin of the spectrum, at Nyquist
+    pub(crate) fn last_bin(&self) -> usize {
+        self.power.len().saturating_sub(1)
+    }
+
+    /// Bin nearest to `frequency`
+    pub(crate) fn bin(&self, frequency: f64) -> usize {
+        ((frequency * self.fft_size as f64 / self.sample_rate).round() as usize)
+            .min(self.last_bin())
+    }
+
+    /// Bins of the main lobe around `peak`
+    pub(crate) fn lobe(&self, peak: usize) -> std::ops::RangeInclusive<usize> {
+        peak.saturating_sub(self.half_width)..=(peak + self.half_width).min(self.last_bin())
+    }
+
+    /// Whether `bin` is within the main lobe of DC
+    pub(crate) fn is_dc(&self, bin: usize) -> bool {
+        bin <= self.half_width
+    }
+
+    /// Strongest bin within the main lobe around `frequency`
+    pub(crate) fn peak_near(&self, frequency: f64) -> usize {
+        self.lobe(self.bin(frequency).max(1))
+            .max_by(|&a, &b| self.power[a].total_cmp(&self.power[b]))
+            .unwrap_or_default()
+    }
+
+    /// Power of the main lobe around `peak`
+    pub(crate) fn lobe_power(&self, peak: usize) -> f64 {
+        self.power
+            .get(self.lobe(peak))
+            .map_or(0.0, |lobe| lobe.iter().sum())
+    }
+
+    /// Total power of bins for which `excluded` is `false`
+    pub(crate) fn power_excluding(&self, excluded: impl Fn(usize) -> bool) -> f64 {
+        self.power
+            .iter()
+            .enumerate()
+            .filter(|&(bin, _)| !excluded(bin))
+            .map(|(_, power)| power)
+            .sum()
+    }
+
+    /// Power of the main lobe around `peak`, without bins for which `excluded` is `true`
+    pub(crate) fn lobe_power_excluding(
+        &self,
+        peak: usize,
+        excluded: impl Fn(usize) -> bool,
+    ) -> f64 {
+        self.lobe(peak)
+            .filter(|&bin| !excluded(bin))
+            .map(|bin| self.power[bin])
+            .sum()
+    }
+
+    /// Strongest bin for which `excluded` is `false`
+    pub(crate) fn strongest_excluding(&self, excluded: impl Fn(usize) -> bool) -> Option<usize> {
+        (0..self.power.len())
+            .filter(|&bin| !excluded(bin))
+            .max_by(|&a, &b| self.power[a].total_cmp(&self.power[b]))
+    }
+
+    /// Sine level in dBFS of a lobe with `lobe_power`
+    pub(crate) fn level(&self, lobe_power: f64) -> f64 {
+        10.0 * (lobe_power / self.enbw).max(MIN_POWER).log10()
+    }
+
+    /// Frequency of the peak at `bin`, refined by a parabola through the log power
+    /// of its neighbours
+    pub(crate) fn peak_frequency(&self, bin: usize) -> f64 {
+        let power = &self.power;
+        let offset = match (bin.checked_sub(1), power.get(bin + 1)) {
+            (Some(previous), Some(&next)) => {
+                let ln = |p: f64| p.max(MIN_POWER).ln();
+                let (a, b, c) = (ln(power[previous]), ln(power[bin]), ln(next));
+                let denominator = a - 2.0 * b + c;
+                if denominator < 0.0 {
+                    (0.5 * (a - c) / denominator).clamp(-0.5, 0.5)
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        };
+        (bin as f64 + offset) * self.sample_rate / self.fft_size as f64
+    }
+}
+
+/// Bar chart of harmonic levels of every output, one chart per group of outputs
+pub(crate) fn generate_harmonics_svg(data: &SnapshotData, config: &HarmonicsConfig) -> String {
+    let chart_config = &config.chart;
+    if data.num_samples == 0 || data.outputs.is_empty() {
+        return "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\"><text>Empty</text></svg>".to_string();
+    }
+
+    let analyses = data.harmonic_analysis(config);
+    let output_charts: Vec<ChannelChartData> = analyses
+        .iter()
+        .enumerate()
+        .map(|(i, analysis)| {
+            let levels: Vec<f32> = std::iter::once(&analysis.fundamental)
+                .chain(&analysis.harmonics)
+                .map(|harmonic| harmonic.level.max(config.min_db) as f32)
+                .collect();
+            ChannelChartData::from_output_data(&levels, &[], i, chart_config)
+        })
+        .collect();
+    let groups = layout_groups(chart_config, vec![], output_charts);
+
+    let svg_width = chart_config.svg_width.unwrap_or(DEFAULT_WIDTH) as u32;
+    let total_height = (chart_config.svg_height_per_channel * groups.len()) as u32;
+
+    let mut svg_buffer = String::new();
+    {
+        let root =
+            SVGBackend::with_string(&mut svg_buffer, (svg_width, total_height)).into_drawing_area();
+
+        let bg_color = parse_hex_color(&chart_config.background_color);
+        root.fill(&bg_color).unwrap();
+
+        let current_area = if let Some(ref title) = chart_config.chart_title {
+            let title_color = get_contrasting_color(&bg_color);
+            let text_style = TextStyle::from(("sans-serif", 20)).color(&title_color);
+            root.titled(title, text_style).unwrap()
+        } else {
+            root
+        };
+
+        let areas = current_area.split_evenly((groups.len(), 1));
+        for (group, area) in groups.iter().zip(areas.iter()) {
+            harmonics_chart(group, &analyses, config, area);
+        }
+
+        current_area.present().unwrap();
+    }
+
+    finish_svg(svg_buffer, chart_config, svg_width, total_height)
+}
+
+/// Bars of `group` channels side by side at every harmonic number
+fn harmonics_chart<DB: DrawingBackend>(
+    group: &[ChannelChartData],
+    analyses: &[HarmonicAnalysis],
+    config: &HarmonicsConfig,
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+) {
+    let chart_config = &config.chart;
+    let axis_color = group_axis_color(group);
+    let num_bars = group
+        .iter()
+        .map(|entry| entry.data.len())
+        .max()
+        .unwrap_or_default()
+        .max(1);
+    let max_db = group
+        .iter()
+        .flat_map(|entry| entry.data.iter())
+        .fold(config.min_db, |max, &db| max.max(db as f64));
+    let y_max = ((max_db / 10.0).ceil() * 10.0).max(config.min_db + 10.0);
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(5)
+        .x_label_area_size(if chart_config.show_labels { 35 } else { 0 })
+        .y_label_area_size(if chart_config.show_labels { 50 } else { 0 })
+        .build_cartesian_2d(0.5..num_bars as f64 + 0.5, config.min_db..y_max)
+        .unwrap();
+
+    let mut mesh = chart.configure_mesh();
+    mesh.axis_style(axis_color.mix(0.3)).disable_x_mesh();
+    if !chart_config.show_grid {
+        mesh.disable_y_mesh();
+    } else {
+        mesh.light_line_style(axis_color.mix(0.1))
+            .bold_line_style(axis_color.mix(0.2));
+    }
+    let x_formatter = |v: &f64| {
+        if (v - v.round()).abs() < 1e-9 {
+            format!("{v:.0}")
+        } else {
+            String::new()
+        }
+    };
+    let y_formatter = |v: &f64| format!("{v:.0}");
+    if chart_config.show_labels {
+        mesh.x_labels(num_bars + 1)
+            .y_labels(5)
+            .x_label_formatter(&x_formatter)
+            .y_label_formatter(&y_formatter)
+            .x_desc("Harmonic")
+            .y_desc("dBFS")
+            .label_style(("sans-serif", 10, &axis_color));
+    }
+    mesh.draw().unwrap();
+
+    let bar_width = 0.8 / group.len().max(1) as f64;
+    for (slot, entry) in group.iter().enumerate() {
+        let color = entry.color;
+        let series = chart
+            .draw_series(entry.data.iter().enumerate().map(|(i, &db)| {
+                let left = i as f64 + 0.6 + slot as f64 * bar_width;
+                Rectangle::new(
+                    [(left, config.min_db), (left + bar_width, db as f64)],
+                    color.filled(),
+                )
+            }))
+            .unwrap();
+
+        let analysis = &analyses[entry.idx];
+        let distortion = format!(
+            "THD {:.4}% ({:.1} dB), THD+N {:.4}% ({:.1} dB) at {:.1} Hz",
+            analysis.thd_percent(),
+            analysis.thd_db(),
+            analysis.thd_n_percent(),
+            analysis.thd_n_db(),
+            analysis.fundamental.frequency,
+        );
+        let label = match &entry.label {
+            Some(label) => format!("{label}: {distortion}"),
+            None => distortion,
+        };
+        series
+            .label(label)
+            .legend(move |(x, y)| Rectangle::new([(x, y - 4), (x + 12, y + 4)], color.filled()));
+    }
+
+    let background = parse_hex_color(&chart_config.background_color);
+    let contrasting = get_contrasting_color(&background);
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .border_style(contrasting)
+        .background_style(background)
+        .label_font(TextStyle::from(("sans-serif", 10)).color(&contrasting))
+        .draw()
+        .unwrap();
+}
@@ -0,0 +1,221 @@
+use std::fmt::Write;
+
+use plotters::style::RGBColor;
+
+use crate::abnormal::AbnormalSample;
+use crate::chart::{Layout, group_axis_color, layout_groups};
+use crate::chart_data::ChannelChartData;
+use crate::config::SvgChartConfig;
+use crate::data::SnapshotData;
+use crate::util::{get_contrasting_color, parse_hex_color};
+
+const VIEWER_SCRIPT: &str = include_str!("html_viewer.js");
+
+const VIEWER_STYLE: &str = "\
+body { margin: 0; padding: 16px; font-family: sans-serif; }
+h1 { margin: 0 0 8px; font-size: 20px; font-weight: normal; text-align: center; }
+#controls { display: flex; flex-wrap: wrap; gap: 12px; align-items: center; font-size: 12px; }
+#controls label { display: inline-flex; align-items: center; gap: 4px; cursor: pointer; }
+#readout { min-height: 16px; margin: 8px 0; font-size: 12px; font-family: monospace; white-space: pre; }
+#charts canvas { display: block; width: 100%; cursor: crosshair; }
+";
+
+fn hex_color(color: RGBColor) -> String {
+    format!("#{:02X}{:02X}{:02X}", color.0, color.1, color.2)
+}
+
+/// Escape text for HTML content and attributes
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// JSON string literal, safe to embed inside a `<script>` element
+fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '<' => json.push_str("\\u003c"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn channel_name(chart: &ChannelChartData) -> String {
+    chart.label.clone().unwrap_or_else(|| {
+        let kind = if chart.is_input { "Input" } else { "Output" };
+        format!("{kind} Ch#{}", chart.idx)
+    })
+}
+
+fn write_channel(json: &mut String, chart: &ChannelChartData, dashed: bool) {
+    write!(
+        json,
+        "{{\"name\":{},\"color\":\"{}\",\"dashed\":{dashed},\"abnormalities\":[",
+        json_string(&channel_name(chart)),
+        hex_color(chart.color)
+    )
+    .unwrap();
+    for (a, (i, abnormality)) in chart.abnormalities.iter().enumerate() {
+        if a > 0 {
+            json.push(',');
+        }
+        let kind = match abnormality {
+            AbnormalSample::Nan => "NaN",
+            AbnormalSample::NegInf => "-inf",
+            AbnormalSample::PosInf => "inf",
+        };
+        write!(json, "[{i},\"{kind}\"]").unwrap();
+    }
+    json.push_str("],\"samples\":[");
+    for (i, sample) in chart.data.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        if sample.is_finite() {
+            write!(json, "{sample:?}").unwrap();
+        } else {
+            json.push_str("null");
+        }
+    }
+    json.push_str("]}");
+}
+
+/// Render a self-contained HTML page with an interactive waveform viewer
+///
+/// Captured samples are embedded as JSON next to an inline script
+/// for zooming, panning, toggling channels and reading values at the cursor
+pub(crate) fn generate_html(data: &SnapshotData, config: &SvgChartConfig) -> String {
+    let input_charts: Vec<ChannelChartData> = if config.with_inputs {
+        data.inputs
+            .iter()
+            .enumerate()
+            .map(|(i, samples)| ChannelChartData::from_input_data(samples, i, config))
+            .collect()
+    } else {
+        vec![]
+    };
+    let output_charts: Vec<ChannelChartData> = data
+        .outputs
+        .iter()
+        .enumerate()
+        .map(|(i, samples)| {
+            let abnormalities = data
+                .abnormalities
+                .get(i)
+                .map(|a| a.as_slice())
+                .unwrap_or_default();
+            ChannelChartData::from_output_data(samples, abnormalities, i, config)
+        })
+        .collect();
+    let num_channels = input_charts.len() + output_charts.len();
+    let groups = layout_groups(config, input_charts, output_charts);
+    // Inputs are dashed when plotted together with outputs, same as SVG charts
+    let dashed_inputs = config.chart_layout == Layout::Combined;
+
+    let background = parse_hex_color(&config.background_color);
+    let foreground = hex_color(get_contrasting_color(&background));
+    let total_height = config.svg_height_per_channel * num_channels;
+    let group_height = total_height / groups.len().max(1);
+
+    let mut json = String::new();
+    write!(
+        json,
+        "{{\"sampleRate\":{:?},\"startSample\":{},\"numSamples\":{},\"background\":{},\"foreground\":\"{foreground}\",\"lineWidth\":{:?},\"showGrid\":{},\"showLabels\":{},\"timeLabels\":{},\"groupHeight\":{group_height},",
+        data.sample_rate,
+        data.start_sample,
+        data.num_samples,
+        json_string(&hex_color(background)),
+        config.line_width,
+        config.show_grid,
+        config.show_labels,
+        config.format_x_axis_labels_as_time,
+    )
+    .unwrap();
+
+    json.push_str("\"groups\":[");
+    let mut channel_index = 0;
+    for (g, group) in groups.iter().enumerate() {
+        if g > 0 {
+            json.push(',');
+        }
+        let title = match group.as_slice() {
+            [single] => single.label.as_deref().map(json_string),
+            _ => None,
+        };
+        write!(
+            json,
+            "{{\"axisColor\":\"{}\",\"title\":{},\"channels\":[",
+            hex_color(group_axis_color(group)),
+            title.unwrap_or_else(|| "null".to_string())
+        )
+        .unwrap();
+        for c in 0..group.len() {
+            if c > 0 {
+                json.push(',');
+            }
+            write!(json, "{}", channel_index + c).unwrap();
+        }
+        json.push_str("]}");
+        channel_index += group.len();
+    }
+
+    json.push_str("],\"channels\":[");
+    for (c, chart) in groups.iter().flatten().enumerate() {
+        if c > 0 {
+            json.push_str(",\n");
+        }
+        write_channel(&mut json, chart, chart.is_input && dashed_inputs);
+    }
+    json.push_str("]}");
+
+    let title = config.chart_title.as_deref().unwrap_or("Snapshot");
+    let width = config
+        .svg_width
+        .map(|width| format!("{width}px"))
+        .unwrap_or_else(|| "100%".to_string());
+
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>").unwrap();
+    writeln!(html, "<html lang=\"en\">").unwrap();
+    writeln!(html, "<head>").unwrap();
+    writeln!(html, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(html, "<title>{}</title>", escape_html(title)).unwrap();
+    writeln!(html, "<style>\n{VIEWER_STYLE}</style>").unwrap();
+    writeln!(html, "</head>").unwrap();
+    writeln!(
+        html,
+        "<body style=\"background: {}; color: {foreground};\">",
+        hex_color(background)
+    )
+    .unwrap();
+    writeln!(html, "<div style=\"width: {width};\">").unwrap();
+    if let Some(title) = &config.chart_title {
+        writeln!(html, "<h1>{}</h1>", escape_html(title)).unwrap();
+    }
+    writeln!(
+        html,
+        "<div id=\"controls\"><span id=\"toggles\"></span><button id=\"reset\" type=\"button\">Reset zoom</button></div>"
+    )
+    .unwrap();
+    writeln!(html, "<div id=\"readout\"></div>").unwrap();
+    writeln!(html, "<div id=\"charts\"></div>").unwrap();
+    writeln!(html, "</div>").unwrap();
+    writeln!(
+        html,
+        "<script type=\"application/json\" id=\"snapshot-data\">\n{json}\n</script>"
+    )
+    .unwrap();
+    writeln!(html, "<script>\n{VIEWER_SCRIPT}</script>").unwrap();
+    writeln!(html, "</body>").unwrap();
+    writeln!(html, "</html>").unwrap();
+
+    html
+}
@@ -0,0 +1,327 @@
+(() => {
+  "use strict";
+
+  const data = JSON.parse(document.getElementById("snapshot-data").textContent);
+  const chartsElement = document.getElementById("charts");
+  const togglesElement = document.getElementById("toggles");
+  const readoutElement = document.getElementById("readout");
+
+  const first = data.startSample;
+  const last = data.startSample + data.numSamples;
+  const minSpan = Math.min(8, data.numSamples);
+  const margin = {
+    left: data.showLabels ? 60 : 10,
+    right: 10,
+    top: 10,
+    bottom: data.showLabels ? 40 : 10,
+  };
+
+  let view = [first, last];
+  let cursor = null;
+  let drag = null;
+  const hidden = new Set();
+
+  const formatX = (x) =>
+    data.timeLabels
+      ? (x / data.sampleRate).toFixed(6).replace(/\.?0+$/, "") + "s"
+      : String(Math.round(x));
+  const formatY = (y) => String(Number(y.toPrecision(4)));
+
+  // Evenly spaced "nice" (1-2-5) ticks covering [from..=to]
+  const ticks = (from, to, count) => {
+    const rough = (to - from) / Math.max(count, 1);
+    const magnitude = Math.pow(10, Math.floor(Math.log10(rough)));
+    const step = [1, 2, 5, 10].map((m) => m * magnitude).find((s) => s >= rough);
+    const result = [];
+    for (let t = Math.ceil(from / step) * step; t <= to; t += step) {
+      result.push(Math.abs(t) < step * 1e-9 ? 0 : t);
+    }
+    return result;
+  };
+
+  const charts = data.groups.map((group) => {
+    const canvas = document.createElement("canvas");
+    canvas.style.height = data.groupHeight + "px";
+    chartsElement.appendChild(canvas);
+    const chart = { canvas, group, plot: null, yRange: null };
+    attachEvents(chart);
+    return chart;
+  });
+
+  data.channels.forEach((channel, c) => {
+    const label = document.createElement("label");
+    label.style.color = channel.color;
+    const checkbox = document.createElement("input");
+    checkbox.type = "checkbox";
+    checkbox.checked = true;
+    checkbox.addEventListener("change", () => {
+      if (checkbox.checked) {
+        hidden.delete(c);
+      } else {
+        hidden.add(c);
+      }
+      drawAll();
+    });
+    label.appendChild(checkbox);
+    label.appendChild(document.createTextNode(channel.name));
+    togglesElement.appendChild(label);
+  });
+
+  document.getElementById("reset").addEventListener("click", () => setView(first, last));
+  window.addEventListener("resize", drawAll);
+
+  function visibleRange() {
+    const from = Math.max(0, Math.floor(view[0] - first));
+    const to = Math.min(data.numSamples, Math.ceil(view[1] - first) + 1);
+    return [from, to];
+  }
+
+  function draw(chart) {
+    const { canvas, group } = chart;
+    const ratio = window.devicePixelRatio || 1;
+    const width = canvas.clientWidth;
+    const height = canvas.clientHeight;
+    canvas.width = Math.round(width * ratio);
+    canvas.height = Math.round(height * ratio);
+    const ctx = canvas.getContext("2d");
+    ctx.setTransform(ratio, 0, 0, ratio, 0, 0);
+    ctx.fillStyle = data.background;
+    ctx.fillRect(0, 0, width, height);
+
+    const plot = {
+      x: margin.left,
+      y: margin.top,
+      w: Math.max(width - margin.left - margin.right, 1),
+      h: Math.max(height - margin.top - margin.bottom, 1),
+    };
+    const channels = group.channels.filter((c) => !hidden.has(c));
+    const [from, to] = visibleRange();
+
+    // Y range of visible samples, padded like SVG charts
+    let min = Infinity;
+    let max = -Infinity;
+    for (const c of channels) {
+      const samples = data.channels[c].samples;
+      for (let i = from; i < to; i++) {
+        const v = samples[i];
+        if (v !== null) {
+          min = Math.min(min, v);
+          max = Math.max(max, v);
+        }
+      }
+    }
+    if (!isFinite(min)) {
+      min = -1;
+      max = 1;
+    }
+    const range = Math.max(max - min, 1e-9);
+    min -= range * 0.1;
+    max += range * 0.1;
+    chart.plot = plot;
+    chart.yRange = [min, max];
+
+    const px = (x) => plot.x + ((x - view[0]) / (view[1] - view[0])) * plot.w;
+    const py = (y) => plot.y + ((max - y) / (max - min)) * plot.h;
+
+    // Axes, grid and labels
+    ctx.globalAlpha = 0.3;
+    ctx.strokeStyle = group.axisColor;
+    ctx.lineWidth = 1;
+    ctx.beginPath();
+    ctx.moveTo(plot.x, plot.y);
+    ctx.lineTo(plot.x, plot.y + plot.h);
+    ctx.lineTo(plot.x + plot.w, plot.y + plot.h);
+    ctx.stroke();
+    ctx.globalAlpha = 1;
+
+    ctx.font = "10px sans-serif";
+    ctx.fillStyle = group.axisColor;
+    for (const t of ticks(view[0], view[1], 8)) {
+      const x = px(t);
+      if (data.showGrid) {
+        ctx.globalAlpha = 0.15;
+        ctx.beginPath();
+        ctx.moveTo(x, plot.y);
+        ctx.lineTo(x, plot.y + plot.h);
+        ctx.stroke();
+        ctx.globalAlpha = 1;
+      }
+      if (data.showLabels) {
+        ctx.textAlign = "center";
+        ctx.textBaseline = "top";
+        ctx.fillText(formatX(t), x, plot.y + plot.h + 5);
+      }
+    }
+    for (const t of ticks(min, max, 4)) {
+      const y = py(t);
+      if (data.showGrid) {
+        ctx.globalAlpha = 0.15;
+        ctx.beginPath();
+        ctx.moveTo(plot.x, y);
+        ctx.lineTo(plot.x + plot.w, y);
+        ctx.stroke();
+        ctx.globalAlpha = 1;
+      }
+      if (data.showLabels) {
+        ctx.textAlign = "right";
+        ctx.textBaseline = "middle";
+        ctx.fillText(formatY(t), plot.x - 5, y);
+      }
+    }
+    if (data.showLabels && group.title) {
+      ctx.textAlign = "center";
+      ctx.textBaseline = "bottom";
+      ctx.fillText(group.title, plot.x + plot.w / 2, height - 2);
+    }
+
+    // Waveforms, min/max per pixel column when samples outnumber pixels
+    ctx.save();
+    ctx.beginPath();
+    ctx.rect(plot.x, plot.y, plot.w, plot.h);
+    ctx.clip();
+    const perPixel = (view[1] - view[0]) / plot.w;
+    for (const c of channels) {
+      const channel = data.channels[c];
+      const samples = channel.samples;
+      ctx.strokeStyle = channel.color;
+      ctx.lineWidth = data.lineWidth;
+      ctx.lineJoin = "round";
+      ctx.setLineDash(channel.dashed ? [2, 3] : []);
+      ctx.beginPath();
+      if (perPixel > 1) {
+        for (let column = 0; column < plot.w; column++) {
+          const a = Math.floor(view[0] - first + column * perPixel);
+          const b = Math.floor(view[0] - first + (column + 1) * perPixel);
+          let low = Infinity;
+          let high = -Infinity;
+          for (let i = Math.max(a, 0); i < Math.min(b, data.numSamples); i++) {
+            const v = samples[i];
+            if (v !== null) {
+              low = Math.min(low, v);
+              high = Math.max(high, v);
+            }
+          }
+          if (low <= high) {
+            ctx.lineTo(plot.x + column, py(low));
+            ctx.lineTo(plot.x + column, py(high));
+          }
+        }
+      } else {
+        let pen = false;
+        for (let i = from; i < to; i++) {
+          const v = samples[i];
+          if (v === null) {
+            pen = false;
+            continue;
+          }
+          if (pen) {
+            ctx.lineTo(px(first + i), py(v));
+          } else {
+            ctx.moveTo(px(first + i), py(v));
+          }
+          pen = true;
+        }
+      }
+      ctx.stroke();
+
+      ctx.setLineDash([]);
+      ctx.fillStyle = "#FF0000";
+      for (const [i, kind] of channel.abnormalities) {
+        if (i < from || i >= to) {
+          continue;
+        }
+        const y = kind === "NaN" ? 0 : kind === "-inf" ? min : max;
+        ctx.beginPath();
+        ctx.arc(px(first + i), py(Math.min(Math.max(y, min), max)), 3, 0, 2 * Math.PI);
+        ctx.fill();
+      }
+    }
+    ctx.restore();
+
+    if (cursor !== null) {
+      ctx.globalAlpha = 0.5;
+      ctx.strokeStyle = data.foreground;
+      ctx.lineWidth = 1;
+      ctx.beginPath();
+      ctx.moveTo(px(cursor), plot.y);
+      ctx.lineTo(px(cursor), plot.y + plot.h);
+      ctx.stroke();
+      ctx.globalAlpha = 1;
+    }
+  }
+
+  function drawAll() {
+    charts.forEach(draw);
+    updateReadout();
+  }
+
+  function updateReadout() {
+    if (cursor === null) {
+      readoutElement.textContent = "Scroll to zoom, drag to pan, double click to reset";
+      return;
+    }
+    const i = cursor - first;
+    const values = data.channels
+      .map((channel, c) => {
+        if (hidden.has(c)) {
+          return null;
+        }
+        const v = channel.samples[i];
+        const abnormality = channel.abnormalities.find(([index]) => index === i);
+        const value = abnormality ? abnormality[1] : v === null ? "-" : String(v);
+        return channel.name + ": " + value;
+      })
+      .filter((value) => value !== null);
+    const time = (cursor / data.sampleRate).toFixed(6).replace(/\.?0+$/, "") + "s";
+    readoutElement.textContent =
+      "Sample " + cursor + " (" + time + ")  " + values.join("  ");
+  }
+
+  function setView(from, to) {
+    const span = Math.min(Math.max(to - from, minSpan), last - first);
+    const start = Math.min(Math.max(from, first), last - span);
+    view = [start, start + span];
+    drawAll();
+  }
+
+  function sampleAt(chart, offsetX) {
+    const t = (offsetX - chart.plot.x) / chart.plot.w;
+    return view[0] + t * (view[1] - view[0]);
+  }
+
+  function attachEvents(chart) {
+    const canvas = chart.canvas;
+    canvas.addEventListener(
+      "wheel",
+      (event) => {
+        event.preventDefault();
+        const anchor = sampleAt(chart, event.offsetX);
+        const factor = Math.exp(event.deltaY * 0.002);
+        setView(anchor - (anchor - view[0]) * factor, anchor + (view[1] - anchor) * factor);
+      },
+      { passive: false },
+    );
+    canvas.addEventListener("mousedown", (event) => {
+      drag = { x: event.clientX, view: view.slice(), width: chart.plot.w };
+    });
+    window.addEventListener("mouseup", () => {
+      drag = null;
+    });
+    canvas.addEventListener("mousemove", (event) => {
+      if (drag) {
+        const shift = ((drag.x - event.clientX) / drag.width) * (drag.view[1] - drag.view[0]);
+        setView(drag.view[0] + shift, drag.view[1] + shift);
+      }
+      const sample = Math.round(sampleAt(chart, event.offsetX));
+      cursor = Math.min(Math.max(sample, first), last - 1);
+      drawAll();
+    });
+    canvas.addEventListener("mouseleave", () => {
+      cursor = null;
+      drawAll();
+    });
+    canvas.addEventListener("dblclick", () => setView(first, last));
+  }
+
+  drawAll();
+})();
@@ -1,5 +1,7 @@
 use fundsp::prelude::AudioUnit;
 
+use crate::error::SnapshotError;
+
 /// Input provided to the audio unit
 #[derive(Default)]
 pub enum InputSource {
@@ -62,58 +64,81 @@ impl InputSource {
     pub fn impulse() -> Self {
         Self::Generator(Box::new(|i, _| if i == 0 { 1.0 } else { 0.0 }))
     }
+    /// Full scale sine wave at `freq` Hz
+    ///
+    /// Phase is computed in `f64` and wrapped every cycle, so long captures stay free
+    /// of the phase noise `f32` would add
     pub fn sine(freq: f32, sample_rate: f32) -> Self {
         Self::Generator(Box::new(move |i, _| {
-            let phase = 2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate;
-            phase.sin()
+            let cycles = (freq as f64 * i as f64 / sample_rate as f64).fract();
+            (2.0 * std::f64::consts::PI * cycles).sin() as f32
         }))
     }
 
+    /// Linear ramp from `from` to `to`, reaching `to` at the last of `num_samples`
+    pub fn ramp(from: f32, to: f32, num_samples: usize) -> Self {
+        let step = (to - from) / num_samples.saturating_sub(1).max(1) as f32;
+        Self::Generator(Box::new(move |i, _| from + step * i as f32))
+    }
+
+    /// Produce input data for `num_inputs` channels of `num_samples` each
+    ///
+    /// Panics when the source doesn't match the requested shape,
+    /// see [InputSource::try_make_data] for a fallible version
     pub fn make_data(&mut self, num_inputs: usize, num_samples: usize) -> Vec<Vec<f32>> {
-        match self {
+        self.try_make_data(num_inputs, num_samples)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Produce input data for `num_inputs` channels of `num_samples` each
+    ///
+    /// Returns an error when the source doesn't match the requested shape
+    pub fn try_make_data(
+        &mut self,
+        num_inputs: usize,
+        num_samples: usize,
+    ) -> Result<Vec<Vec<f32>>, SnapshotError> {
+        let data = match self {
             InputSource::None => vec![vec![0.0; num_samples]; num_inputs],
             InputSource::VecByChannel(data) => {
-                assert_eq!(
-                    data.len(),
-                    num_inputs,
-                    "Input vec size mismatch. Expected {} channels, got {}",
-                    num_inputs,
-                    data.len()
-                );
-                assert!(
-                    data.iter().all(|v| v.len() == num_samples),
-                    "Input vec size mismatch. Expected {} samples per channel, got {}",
-                    num_samples,
-                    data.iter().map(|v| v.len()).max().unwrap_or(0)
-                );
+                if data.len() != num_inputs {
+                    return Err(SnapshotError::InputChannelMismatch {
+                        expected: num_inputs,
+                        actual: data.len(),
+                    });
+                }
+                if let Some(len) = data.iter().map(|v| v.len()).find(|&l| l != num_samples) {
+                    return Err(SnapshotError::InputLengthMismatch {
+                        expected: num_samples,
+                        actual: len,
+                    });
+                }
                 data.to_vec()
             }
             InputSource::VecByTick(data) => {
-                assert!(
-                    data.iter().all(|v| v.len() == num_inputs),
-                    "Input vec size mismatch. Expected {} channels, got {}",
-                    num_inputs,
-                    data.iter().map(|v| v.len()).max().unwrap_or(0)
-                );
-                assert_eq!(
-                    data.len(),
-                    num_samples,
-                    "Input vec size mismatch. Expected {} samples, got {}",
-                    num_samples,
-                    data.len()
-                );
+                if let Some(len) = data.iter().map(|v| v.len()).find(|&l| l != num_inputs) {
+                    return Err(SnapshotError::InputChannelMismatch {
+                        expected: num_inputs,
+                        actual: len,
+                    });
+                }
+                if data.len() != num_samples {
+                    return Err(SnapshotError::InputLengthMismatch {
+                        expected: num_samples,
+                        actual: data.len(),
+                    });
+                }
                 (0..num_inputs)
                     .map(|ch| (0..num_samples).map(|i| data[i][ch]).collect())
                     .collect()
             }
             InputSource::Flat(data) => {
-                assert_eq!(
-                    data.len(),
-                    num_inputs,
-                    "Input vec size mismatch. Expected {} channels, got {}",
-                    num_inputs,
-                    data.len()
-                );
+                if data.len() != num_inputs {
+                    return Err(SnapshotError::InputChannelMismatch {
+                        expected: num_inputs,
+                        actual: data.len(),
+                    });
+                }
                 (0..num_inputs)
                     .map(|ch| (0..num_samples).map(|_| data[ch]).collect())
                     .collect()
@@ -148,6 +173,8 @@ impl InputSource {
                 }
                 data
             }
-        }
+        };
+
+        Ok(data)
     }
 }
@@ -1,12 +1,32 @@
 #![doc = include_str!("../README.md")]
 
 mod abnormal;
+mod annotation;
+#[cfg(feature = "png")]
+mod bitmap;
 mod chart;
 mod chart_data;
+mod decimation;
+mod html;
+mod raw;
+mod response_chart;
+mod series;
+mod spectrogram;
+mod spectrum;
+mod svg_path;
+mod text;
+mod text_chart;
+mod transfer_curve;
 mod util;
 mod wav;
+mod xy_chart;
+mod y_scale;
 
+pub mod aliasing;
+pub mod compare;
 pub mod config;
+pub mod data;
+pub mod error;
 #[cfg(feature = "dot")]
 pub mod graph;
 
@@ -20,19 +40,34 @@ pub mod graph {
         b"digraph { /* dot feature disabled */ }".to_vec()
     }
 }
+pub mod harmonics;
 pub mod input;
+pub mod loudness;
 pub mod macros;
+pub mod response;
 pub mod snapshot;
+pub mod statistics;
 pub mod warmup;
 
 pub mod prelude {
+    pub use crate::aliasing::*;
     pub use crate::chart::Layout;
+    pub use crate::compare::*;
     pub use crate::config::*;
+    pub use crate::data::*;
+    pub use crate::error::*;
     pub use crate::graph::*;
+    pub use crate::harmonics::*;
     pub use crate::input::*;
+    pub use crate::loudness::*;
+    pub use crate::response::*;
     pub use crate::snapshot::*;
+    pub use crate::statistics::*;
     pub use crate::warmup::*;
-    pub use crate::{assert_audio_unit_snapshot, assert_dsp_net_snapshot};
+    pub use crate::{
+        assert_aliasing_snapshot, assert_audio_unit_snapshot, assert_dsp_net_snapshot,
+        assert_harmonics_snapshot, assert_transfer_curve_snapshot,
+    };
 }
 
 #[cfg(all(test, feature = "dot"))]
@@ -0,0 +1,295 @@
+use std::f64::consts::PI;
+
+use crate::config::LoudnessLimits;
+use crate::error::SnapshotError;
+
+/// Length of momentary loudness and gating blocks in seconds
+const MOMENTARY_WINDOW: f64 = 0.4;
+/// Length of short-term loudness windows in seconds
+const SHORT_TERM_WINDOW: f64 = 3.0;
+/// Hop between consecutive blocks in seconds, 75% overlap of momentary blocks
+const BLOCK_HOP: f64 = 0.1;
+/// Blocks quieter than this are excluded from integrated loudness
+const ABSOLUTE_GATE: f64 = -70.0;
+/// Blocks quieter than the ungated loudness by this many LU are excluded from integrated loudness
+const RELATIVE_GATE: f64 = -10.0;
+/// Oversampling factor of true-peak measurement
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+/// Input samples on each side of an interpolated true-peak sample
+const TRUE_PEAK_HALF_TAPS: isize = 6;
+
+/// ITU-R BS.1770 loudness and true-peak of captured output channels
+///
+/// Levels are `f64::NEG_INFINITY` for silence and for captures
+/// shorter than the measurement window: 400 ms for momentary and integrated loudness,
+/// 3 s for short-term loudness
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let config = SnapshotConfigBuilder::default()
+///     .num_samples(44100)
+///     .build()
+///     .unwrap();
+/// let data = capture_audio_unit(sine_hz::<f32>(997.0) * 0.5, InputSource::None, &config);
+/// let loudness = data.loudness();
+/// assert!((loudness.integrated + 9.0).abs() < 0.1);
+/// assert!((loudness.true_peak + 6.02).abs() < 0.1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Loudness {
+    /// Gated integrated loudness in LUFS
+    pub integrated: f64,
+    /// Loudest 3 s window in LUFS
+    pub max_short_term: f64,
+    /// Loudest 400 ms window in LUFS
+    pub max_momentary: f64,
+    /// Highest peak of 4x oversampled channels in dBTP
+    pub true_peak: f64,
+}
+
+impl Loudness {
+    /// Measure loudness of `channels` sampled at `sample_rate`
+    ///
+    /// Channels are weighted equally, except the surround channels of 6 channel (5.1) audio
+    /// which are weighted by 1.41 and the LFE channel which is ignored,
+    /// assuming L, R, C, LFE, Ls, Rs order
+    pub fn measure(channels: &[Vec<f32>], sample_rate: f64) -> Self {
+        let weighted: Vec<Vec<f64>> = channels
+            .iter()
+            .map(|samples| k_weighting(samples, sample_rate))
+            .collect();
+        let weights: Vec<f64> = (0..channels.len())
+            .map(|ch| channel_weight(ch, channels.len()))
+            .collect();
+
+        let momentary = block_powers(&weighted, &weights, sample_rate, MOMENTARY_WINDOW);
+        let short_term = block_powers(&weighted, &weights, sample_rate, SHORT_TERM_WINDOW);
+        let max_loudness = |powers: &[f64]| {
+            powers
+                .iter()
+                .map(|&power| loudness(power))
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+
+        Self {
+            integrated: integrated_loudness(&momentary),
+            max_short_term: max_loudness(&short_term),
+            max_momentary: max_loudness(&momentary),
+            true_peak: channels
+                .iter()
+                .map(|samples| 20.0 * true_peak(samples).log10())
+                .fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+
+    /// Check measured levels against `limits`
+    ///
+    /// Returns the first exceeded limit as [SnapshotError::LoudnessLimitExceeded]
+    pub fn check(&self, limits: &LoudnessLimits) -> Result<(), SnapshotError> {
+        let exceeded = |metric: &'static str, value: f64, limit: f64| {
+            Err(SnapshotError::LoudnessLimitExceeded {
+                metric,
+                value,
+                limit,
+            })
+        };
+
+        if let Some(target) = limits.target_integrated {
+            let tolerance = limits.integrated_tolerance.abs();
+            if self.integrated < target - tolerance {
+                return exceeded("integrated loudness", self.integrated, target - tolerance);
+            }
+            if self.integrated > target + tolerance {
+                return exceeded("integrated loudness", self.integrated, target + tolerance);
+            }
+        }
+        if let Some(limit) = limits.max_short_term
+            && self.max_short_term > limit
+        {
+            return exceeded("short-term loudness", self.max_short_term, limit);
+        }
+        if let Some(limit) = limits.max_momentary
+            && self.max_momentary > limit
+        {
+            return exceeded("momentary loudness", self.max_momentary, limit);
+        }
+        if let Some(limit) = limits.max_true_peak
+            && self.true_peak > limit
+        {
+            return exceeded("true peak", self.true_peak, limit);
+        }
+        Ok(())
+    }
+
+    /// One line summary, e.g. for chart footers and snapshot metadata
+    pub fn summary(&self) -> String {
+        format!(
+            "integrated {} LUFS, short-term max {} LUFS, momentary max {} LUFS, true peak {} dBTP",
+            level_text(self.integrated),
+            level_text(self.max_short_term),
+            level_text(self.max_momentary),
+            level_text(self.true_peak),
+        )
+    }
+}
+
+fn level_text(level: f64) -> String {
+    if level.is_finite() {
+        format!("{level:.2}")
+    } else {
+        "-inf".to_string()
+    }
+}
+
+fn channel_weight(channel: usize, num_channels: usize) -> f64 {
+    match (num_channels, channel) {
+        (6, 3) => 0.0,
+        (6, 4 | 5) => 1.41,
+        _ => 1.0,
+    }
+}
+
+/// Loudness in LUFS of weighted mean square `power`
+fn loudness(power: f64) -> f64 {
+    -0.691 + 10.0 * power.log10()
+}
+
+/// Biquad filter in direct form I, `[b0, b1, b2]` and `[a1, a2]` normalized by `a0`
+fn biquad(samples: impl Iterator<Item = f64>, b: [f64; 3], a: [f64; 2]) -> Vec<f64> {
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+    samples
+        .map(|x| {
+            let y = b[0] * x + b[1] * x1 + b[2] * x2 - a[0] * y1 - a[1] * y2;
+            (x2, x1, y2, y1) = (x1, x, y1, y);
+            y
+        })
+        .collect()
+}
+
+/// K-weighting of BS.1770: high shelf modelling the head, followed by RLB high-pass
+///
+/// Coefficients are derived for any `sample_rate`, matching the tabulated ones at 48 kHz
+fn k_weighting(samples: &[f32], sample_rate: f64) -> Vec<f64> {
+    let (f0, gain_db, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (PI * f0 / sample_rate).tan();
+    let vh = 10.0_f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = biquad(
+        samples.iter().map(|&s| s as f64),
+        [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (PI * f0 / sample_rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    biquad(
+        shelf.into_iter(),
+        [1.0, -2.0, 1.0],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    )
+}
+
+/// Weighted sum of channel mean squares of every `window` seconds long block, 100 ms apart
+fn block_powers(weighted: &[Vec<f64>], weights: &[f64], sample_rate: f64, window: f64) -> Vec<f64> {
+    let block_len = (window * sample_rate).round() as usize;
+    let hop = ((BLOCK_HOP * sample_rate).round() as usize).max(1);
+    let num_samples = weighted.first().map_or(0, |samples| samples.len());
+    if block_len == 0 || num_samples < block_len {
+        return vec![];
+    }
+
+    (0..=(num_samples - block_len) / hop)
+        .map(|block| {
+            let range = block * hop..block * hop + block_len;
+            weighted
+                .iter()
+                .zip(weights)
+                .map(|(samples, weight)| {
+                    let sum: f64 = samples[range.clone()].iter().map(|s| s * s).sum();
+                    weight * sum / block_len as f64
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// Integrated loudness of gating block powers, with absolute and relative gates
+fn integrated_loudness(block_powers: &[f64]) -> f64 {
+    let gated_mean = |gate: f64| {
+        let gated: Vec<f64> = block_powers
+            .iter()
+            .copied()
+            .filter(|&power| loudness(power) > gate)
+            .collect();
+        if gated.is_empty() {
+            None
+        } else {
+            Some(gated.iter().sum::<f64>() / gated.len() as f64)
+        }
+    };
+
+    let Some(ungated) = gated_mean(ABSOLUTE_GATE) else {
+        return f64::NEG_INFINITY;
+    };
+    let relative_gate = (loudness(ungated) + RELATIVE_GATE).max(ABSOLUTE_GATE);
+    gated_mean(relative_gate).map_or(f64::NEG_INFINITY, loudness)
+}
+
+/// Highest absolute value of `samples` interpolated at 4x their sample rate
+///
+/// Interpolates with a Hann windowed sinc over 12 input samples
+fn true_peak(samples: &[f32]) -> f64 {
+    let sample_peak = samples
+        .iter()
+        .fold(0.0_f64, |peak, &s| peak.max((s as f64).abs()));
+
+    let kernels: Vec<Vec<f64>> = (1..TRUE_PEAK_OVERSAMPLING)
+        .map(|phase| {
+            let offset = phase as f64 / TRUE_PEAK_OVERSAMPLING as f64;
+            let kernel: Vec<f64> = (1 - TRUE_PEAK_HALF_TAPS..=TRUE_PEAK_HALF_TAPS)
+                .map(|tap| {
+                    let t = offset - tap as f64;
+                    let sinc = if t == 0.0 {
+                        1.0
+                    } else {
+                        (PI * t).sin() / (PI * t)
+                    };
+                    let window = 0.5 * (1.0 + (PI * t / TRUE_PEAK_HALF_TAPS as f64).cos());
+                    sinc * window
+                })
+                .collect();
+            // Unity gain at DC
+            let sum: f64 = kernel.iter().sum();
+            kernel.into_iter().map(|tap| tap / sum).collect()
+        })
+        .collect();
+
+    let sample = |i: isize| {
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| samples.get(i))
+            .map_or(0.0, |&s| s as f64)
+    };
+    let interpolated_peak = (0..samples.len() as isize)
+        .flat_map(|i| {
+            kernels.iter().map(move |kernel| {
+                kernel
+                    .iter()
+                    .zip(1 - TRUE_PEAK_HALF_TAPS..)
+                    .map(|(tap, offset)| tap * sample(i + offset))
+                    .sum::<f64>()
+                    .abs()
+            })
+        })
+        .fold(0.0, f64::max);
+
+    sample_peak.max(interpolated_peak)
+}
@@ -5,12 +5,14 @@
 /// 1. An SVG chart (default `SvgChartConfig`)
 /// 2. A 16-bit WAV file (`WavOutput::Wav16`)
 ///
-/// Arms that accept a custom `SnapshotConfig` produce exactly ONE snapshot
-/// using the `output_mode` specified in that config.
+/// Arms that accept a custom `SnapshotConfig` produce one snapshot
+/// per output mode of that config: `output_mode` and every `extra_output_modes` entry.
 ///
-/// The unit is internally cloned so it can be processed twice (SVG + WAV)
-/// without triggering a move error. If your audio unit type does not implement
-/// `Clone`, use the config form and select a single output mode.
+/// The unit is processed exactly once, all snapshots are rendered from that single run.
+/// Units and input sources don't need to implement `Clone`.
+///
+/// Text output modes (`Csv`, `Json`, `Text`) are stored inside `.snap` files,
+/// so insta reviews and PR diffs show changed samples line by line.
 ///
 /// ## Examples
 ///
@@ -35,7 +37,7 @@
 /// use fundsp::prelude::*;
 /// use insta_fun::prelude::*;
 ///
-/// // With input source and custom config (generates only one file per config.output_mode)
+/// // With input source and custom config (generates one file per config output mode)
 /// let chart = SvgChartConfigBuilder::default().chart_title("Highpass").build().unwrap();
 /// let config = SnapshotConfigBuilder::default()
 ///     .num_samples(512)
@@ -68,158 +70,187 @@
 /// );
 /// ```
 ///
+/// ```rust,no_run
+/// use fundsp::prelude::*;
+/// use insta_fun::prelude::*;
+///
+/// // Several output modes from one processing run (doc_multi.svg & doc_multi.wav)
+/// let config = SnapshotConfigBuilder::default()
+///     .num_samples(512)
+///     .extra_output_mode(WavOutput::Wav32)
+///     .build()
+///     .unwrap();
+/// assert_audio_unit_snapshot!(
+///     "doc_multi",
+///     sine_hz::<f32>(440.0),
+///     InputSource::None,
+///     config
+/// );
+/// ```
+///
 /// Invariants / Notes:
-/// - Unit expression is evaluated once per macro invocation; cloned only for dual-snapshot arms.
+/// - Unit, input and config expressions are evaluated exactly once per macro invocation.
 /// - Name expression (in widened arms) is evaluated exactly once and converted with Into<String>.
-/// - Input expression in the (name, unit, input) arm is evaluated twice (once per SVG, once per WAV).
 /// - Two-arg (name, unit) arm keeps `$name:literal` to avoid ambiguity with the (unit, config) arm.
 ///   To use a dynamic (non-literal) name, supply an input or a config (3 or 4 argument forms).
 #[macro_export]
 macro_rules! assert_audio_unit_snapshot {
     // With just the unit (SVG + WAV16)
     ($unit:expr) => {{
-        // Capture unit once; clone for second snapshot to avoid move error.
-        let mut __unit = $unit;
-        let __unit_clone = __unit.clone();
-
-        // SVG
-        let config = $crate::config::SnapshotConfigBuilder::default()
-            .build()
-            .unwrap();
-        let name = config.file_name(None);
-        let data_svg = $crate::snapshot::snapshot_audio_unit_with_options(__unit, config);
-
-        ::insta::with_settings!({ omit_expression => true}, {
-            ::insta::assert_binary_snapshot!(&name, data_svg.as_slice().to_vec());
-        });
-
-        // WAV16
         let config = $crate::config::SnapshotConfigBuilder::default()
-            .output_mode($crate::config::WavOutput::Wav16)
+            .extra_output_mode($crate::config::WavOutput::Wav16)
             .build()
             .unwrap();
-        let name = config.file_name(None);
-        let data_wav = $crate::snapshot::snapshot_audio_unit_with_options(__unit_clone, config);
+        let data =
+            $crate::snapshot::capture_audio_unit($unit, $crate::input::InputSource::None, &config);
 
-        ::insta::with_settings!({ omit_expression => true, snapshot_suffix => "audio" }, {
-            ::insta::assert_binary_snapshot!(&name, data_wav.as_slice().to_vec());
-        });
+        $crate::__assert_snapshot_outputs!(None, data, config);
     }};
 
     // With name and unit (name.svg + name.wav) - kept literal for disambiguation.
     ($name:literal, $unit:expr) => {{
-        let mut __unit = $unit;
-        let __unit_clone = __unit.clone();
-
-        // SVG
         let config = $crate::config::SnapshotConfigBuilder::default()
             .chart_title($name)
+            .extra_output_mode($crate::config::WavOutput::Wav16)
             .build()
             .unwrap();
-        let name = config.file_name(Some($name));
-        let data_svg = $crate::snapshot::snapshot_audio_unit_with_options(__unit, config);
-
-        ::insta::with_settings!({ omit_expression => true}, {
-            ::insta::assert_binary_snapshot!(&name, data_svg.as_slice().to_vec());
-        });
-
-        // WAV16
-        let config = $crate::config::SnapshotConfigBuilder::default()
-            .output_mode($crate::config::WavOutput::Wav16)
-            .build()
-            .unwrap();
-        let name = config.file_name(Some($name));
-        let data_wav = $crate::snapshot::snapshot_audio_unit_with_options(__unit_clone, config);
+        let data =
+            $crate::snapshot::capture_audio_unit($unit, $crate::input::InputSource::None, &config);
 
-        ::insta::with_settings!({ omit_expression => true, snapshot_suffix => "audio" }, {
-            ::insta::assert_binary_snapshot!(&name, data_wav.as_slice().to_vec());
-        });
+        $crate::__assert_snapshot_outputs!(Some($name), data, config);
     }};
 
     // With input source (name.svg + name.wav) - widened name
     ($name:expr, $unit:expr, $input:expr) => {{
         let __name: String = ::std::convert::Into::into($name);
-        let mut __unit = $unit;
-        let __unit_clone = __unit.clone();
-        // Input expression is evaluated twice; prefer passing a cheap expression (e.g. InputSource::impulse()).
-        // If you have a bound variable you need two independent values.
-
-        // SVG
         let config = $crate::config::SnapshotConfigBuilder::default()
             .chart_title(__name.as_str())
+            .extra_output_mode($crate::config::WavOutput::Wav16)
             .build()
             .unwrap();
-        let name = config.file_name(Some(__name.as_str()));
-        let data_svg =
-            $crate::snapshot::snapshot_audio_unit_with_input_and_options(__unit, $input, config);
-
-        ::insta::with_settings!({ omit_expression => true}, {
-            ::insta::assert_binary_snapshot!(&name, data_svg.as_slice().to_vec());
-        });
-
-        // WAV16
-        let config = $crate::config::SnapshotConfigBuilder::default()
-            .output_mode($crate::config::WavOutput::Wav16)
-            .build()
-            .unwrap();
-        let name = config.file_name(Some(__name.as_str()));
-        let data_wav =
-            $crate::snapshot::snapshot_audio_unit_with_input_and_options(__unit_clone, $input, config);
+        let data = $crate::snapshot::capture_audio_unit($unit, $input, &config);
 
-        ::insta::with_settings!({ omit_expression => true, snapshot_suffix => "audio" }, {
-            ::insta::assert_binary_snapshot!(&name, data_wav.as_slice().to_vec());
-        });
+        $crate::__assert_snapshot_outputs!(Some(__name.as_str()), data, config);
     }};
 
-    // With input source and config (single snapshot; uses config.output_mode) - widened name
+    // With input source and config (one snapshot per config output mode) - widened name
     ($name:expr, $unit:expr, $input:expr, $config:expr) => {{
         let __name: String = ::std::convert::Into::into($name);
         let mut config = $config;
         config.maybe_title(__name.as_str());
 
-        let is_audio = matches!(
-            config.output_mode,
-            $crate::config::SnapshotOutputMode::Wav(_)
-        );
-
-        let name = config.file_name(Some(__name.as_str()));
-        let data = $crate::snapshot::snapshot_audio_unit_with_input_and_options($unit, $input, config);
+        let data = $crate::snapshot::capture_audio_unit($unit, $input, &config);
 
-        if is_audio {
-            ::insta::with_settings!({ omit_expression => true, snapshot_suffix => "audio" }, {
-                ::insta::assert_binary_snapshot!(&name, data.as_slice().to_vec());
-            });
-        }
-        else {
-            ::insta::with_settings!({ omit_expression => true}, {
-                ::insta::assert_binary_snapshot!(&name, data.as_slice().to_vec());
-            });
-        }
+        $crate::__assert_snapshot_outputs!(Some(__name.as_str()), data, config);
     }};
 
-    // With unit and config (single snapshot; uses config.output_mode)
+    // With unit and config (one snapshot per config output mode)
     // NOTE: The (name, unit) two-arg arm keeps name as a literal to avoid ambiguity with this (unit, config) arm.
     // If you need a dynamic (non-literal) name, use a 3-arg or 4-arg form (name, unit, input[, config]) which are widened to $name:expr.
-        ($unit:expr, $config:expr) => {{
+    ($unit:expr, $config:expr) => {{
+        let config = $config;
+        let data =
+            $crate::snapshot::capture_audio_unit($unit, $crate::input::InputSource::None, &config);
 
-        let is_audio = matches!(
-            $config.output_mode,
-            $crate::config::SnapshotOutputMode::Wav(_)
-        );
-        // Capture name before moving config into processing.
-        let name = $config.file_name(None);
-        let data = $crate::snapshot::snapshot_audio_unit_with_options($unit, $config);
-
-        if is_audio {
-            ::insta::with_settings!({ omit_expression => true, snapshot_suffix => "audio" }, {
-                ::insta::assert_binary_snapshot!(&name, data.as_slice().to_vec());
-            });
+        $crate::__assert_snapshot_outputs!(None, data, config);
+    }};
+}
+
+/// Intended for internal use only
+///
+/// Renders every output mode of the config from captured data and asserts each as a binary snapshot
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_snapshot_outputs {
+    ($name:expr, $data:expr, $config:expr) => {{
+        let __name: Option<&str> = $name;
+        let __description: Option<String> = $config.snapshot_description(&$data);
+        for __output_mode in $config.output_modes() {
+            let mut __file_name = __output_mode.file_name(__name);
+            let mut __bytes = $data.render(__output_mode);
+            // Why tolerance didn't apply, reported if the assertion fails
+            let mut __tolerance_note: Option<String> = None;
+
+            if let Some(__tolerance) = __output_mode.tolerance() {
+                if __file_name.starts_with('.') {
+                    __file_name = format!(
+                        "{}{}",
+                        $crate::compare::__unnamed_snapshot_name($crate::__function_name!()),
+                        __file_name
+                    );
+                }
+                let __settings = ::insta::Settings::clone_current();
+                let __stored = $crate::compare::__stored_snapshot_path(
+                    &$crate::compare::__workspace_root(
+                        ::std::env!("CARGO_MANIFEST_DIR"),
+                        ::std::option_env!("INSTA_WORKSPACE_ROOT"),
+                        ::std::file!(),
+                    ),
+                    ::std::file!(),
+                    __settings.snapshot_path(),
+                    __settings
+                        .prepend_module_to_snapshot()
+                        .then_some(::std::module_path!()),
+                    &__file_name,
+                    __output_mode.snapshot_suffix(),
+                );
+                (__bytes, __tolerance_note) =
+                    $crate::compare::__tolerant_snapshot_bytes(&__stored, __bytes, __tolerance);
+            }
+
+            let mut __settings = ::insta::Settings::clone_current();
+            __settings.set_omit_expression(true);
+            if let Some(__description) = &__description {
+                __settings.set_description(__description.as_str());
+            }
+            if let Some(__suffix) = __output_mode.snapshot_suffix() {
+                __settings.set_snapshot_suffix(__suffix);
+            }
+            let __assertion = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
+                __settings.bind(|| {
+                    if __output_mode.is_text() {
+                        let __snapshot_name: Option<String> = __file_name
+                            .rsplit_once('.')
+                            .map(|(__stem, _)| __stem.to_string())
+                            .filter(|__stem| !__stem.is_empty());
+                        let __text =
+                            String::from_utf8(__bytes).expect("text snapshot is valid UTF-8");
+                        ::insta::assert_snapshot!(__snapshot_name, __text);
+                    } else {
+                        ::insta::assert_binary_snapshot!(&__file_name, __bytes);
+                    }
+                })
+            }));
+            if let Err(__panic) = __assertion {
+                match &__tolerance_note {
+                    Some(__note) => ::std::panic!(
+                        "{}\n{}",
+                        $crate::compare::__panic_message(&*__panic),
+                        __note
+                    ),
+                    None => ::std::panic::resume_unwind(__panic),
+                }
+            }
+        }
+    }};
+}
+
+/// Intended for internal use only
+///
+/// Path of the enclosing function, e.g. `my_crate::tests::test_lowpass`
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __function_name {
+    () => {{
+        fn __f() {}
+        fn __type_name_of<T>(_: T) -> &'static str {
+            ::std::any::type_name::<T>()
         }
-        else {
-            ::insta::with_settings!({ omit_expression => true }, {
-                ::insta::assert_binary_snapshot!(&name, data.as_slice().to_vec());
-            });
+        let mut __name = __type_name_of(__f).strip_suffix("::__f").unwrap_or("");
+        while let Some(__rest) = __name.strip_suffix("::{{closure}}") {
+            __name = __rest;
         }
+        __name
     }};
 }
 
@@ -246,3 +277,136 @@ macro_rules! assert_dsp_net_snapshot {
         });
     }};
 }
+
+/// Macro to snapshot the transfer curve of a static waveshaper as SVG using `snapshot_transfer_curve`
+///
+/// ### Usage:
+///
+/// `assert_transfer_curve_snapshot!(name_expr, unit_expr);`
+///
+/// `assert_transfer_curve_snapshot!(name_expr, unit_expr, transfer_curve_config_expr);`
+///
+/// - name_expr: names the snapshot file (e.g., "tanh" produces `tanh.svg`)
+///   and titles the chart unless the config sets a title
+/// - unit_expr: audio unit fed with the input ramp
+/// - transfer_curve_config_expr: `TransferCurveConfig`, default config when omitted
+///
+/// ```rust,no_run
+/// use fundsp::prelude::*;
+/// use insta_fun::prelude::*;
+///
+/// assert_transfer_curve_snapshot!("doc_tanh", shape(Tanh(2.0)));
+/// ```
+#[macro_export]
+macro_rules! assert_transfer_curve_snapshot {
+    ($name:expr, $unit:expr) => {{
+        $crate::assert_transfer_curve_snapshot!(
+            $name,
+            $unit,
+            $crate::config::TransferCurveConfig::default()
+        );
+    }};
+
+    ($name:expr, $unit:expr, $config:expr) => {{
+        let __name: String = ::std::convert::Into::into($name);
+        let mut __config: $crate::config::TransferCurveConfig = $config;
+        if __config.chart.chart_title.is_none() {
+            __config.chart.chart_title = Some(__name.clone());
+        }
+        let __snap_name = format!("{}.svg", __name);
+        let __bytes: ::std::vec::Vec<u8> =
+            $crate::snapshot::snapshot_transfer_curve($unit, __config);
+        ::insta::with_settings!({ omit_expression => true }, {
+            ::insta::assert_binary_snapshot!(&__snap_name, __bytes);
+        });
+    }};
+}
+
+/// Macro to snapshot harmonic distortion of a unit as SVG using `snapshot_harmonics`
+///
+/// ### Usage:
+///
+/// `assert_harmonics_snapshot!(name_expr, unit_expr);`
+///
+/// `assert_harmonics_snapshot!(name_expr, unit_expr, harmonics_config_expr);`
+///
+/// - name_expr: names the snapshot file (e.g., "saturator" produces `saturator.svg`)
+///   and titles the chart unless the config sets a title
+/// - unit_expr: audio unit driven by a sine, or a free-running oscillator without inputs
+/// - harmonics_config_expr: `HarmonicsConfig`, default config when omitted
+///
+/// ```rust,no_run
+/// use fundsp::prelude::*;
+/// use insta_fun::prelude::*;
+///
+/// assert_harmonics_snapshot!("doc_saturator", shape(Tanh(2.0)));
+/// ```
+#[macro_export]
+macro_rules! assert_harmonics_snapshot {
+    ($name:expr, $unit:expr) => {{
+        $crate::assert_harmonics_snapshot!(
+            $name,
+            $unit,
+            $crate::config::HarmonicsConfig::default()
+        );
+    }};
+
+    ($name:expr, $unit:expr, $config:expr) => {{
+        let __name: String = ::std::convert::Into::into($name);
+        let mut __config: $crate::config::HarmonicsConfig = $config;
+        if __config.chart.chart_title.is_none() {
+            __config.chart.chart_title = Some(__name.clone());
+        }
+        let __snap_name = format!("{}.svg", __name);
+        let __bytes: ::std::vec::Vec<u8> = $crate::snapshot::snapshot_harmonics($unit, __config);
+        ::insta::with_settings!({ omit_expression => true }, {
+            ::insta::assert_binary_snapshot!(&__snap_name, __bytes);
+        });
+    }};
+}
+
+/// Macro to snapshot aliasing of a unit across a frequency sweep as SVG using `snapshot_aliasing`
+///
+/// Panics when an alias level exceeds `max_alias_level` of the config
+///
+/// ### Usage:
+///
+/// `assert_aliasing_snapshot!(name_expr, make_unit_expr);`
+///
+/// `assert_aliasing_snapshot!(name_expr, make_unit_expr, aliasing_config_expr);`
+///
+/// - name_expr: names the snapshot file (e.g., "saw" produces `saw.svg`)
+///   and titles the chart unless the config sets a title
+/// - make_unit_expr: closure building the unit for each swept frequency in Hz
+/// - aliasing_config_expr: `AliasingConfig`, default config when omitted
+///
+/// ```rust,no_run
+/// use fundsp::prelude::*;
+/// use insta_fun::prelude::*;
+///
+/// assert_aliasing_snapshot!("doc_saw", |frequency| saw_hz(frequency));
+/// ```
+#[macro_export]
+macro_rules! assert_aliasing_snapshot {
+    ($name:expr, $make_unit:expr) => {{
+        $crate::assert_aliasing_snapshot!(
+            $name,
+            $make_unit,
+            $crate::config::AliasingConfig::default()
+        );
+    }};
+
+    ($name:expr, $make_unit:expr, $config:expr) => {{
+        let __name: String = ::std::convert::Into::into($name);
+        let mut __config: $crate::config::AliasingConfig = $config;
+        if __config.chart.chart_title.is_none() {
+            __config.chart.chart_title = Some(__name.clone());
+        }
+        let __snap_name = format!("{}.svg", __name);
+        let __bytes: ::std::vec::Vec<u8> =
+            $crate::snapshot::snapshot_aliasing($make_unit, __config);
+        ::insta::with_settings!({ omit_expression => true }, {
+            ::insta::assert_binary_snapshot!(&__snap_name, __bytes);
+        });
+    }};
+}
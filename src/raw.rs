@@ -0,0 +1,176 @@
+use crate::abnormal::AbnormalSample;
+use crate::config::Processing;
+use crate::data::SnapshotData;
+use crate::error::SnapshotError;
+
+const MAGIC: &[u8; 8] = b"INSTAFUN";
+const VERSION: u8 = 1;
+
+/// Encode captured data losslessly
+///
+/// Layout (little-endian):
+/// - magic `INSTAFUN`, format version `u8`
+/// - sample rate `f64`, start sample `u64`, number of samples `u64`
+/// - processing mode tag `u8` and batch size `u8`
+/// - number of inputs `u32`, number of outputs `u32`
+/// - input then output samples as `f32`, channel by channel
+/// - per output channel: number of abnormal samples `u32`, then `(index u64, kind u8)` pairs
+pub(crate) fn encode_raw(data: &SnapshotData) -> Vec<u8> {
+    let mut buffer = Vec::new();
+
+    buffer.extend_from_slice(MAGIC);
+    buffer.push(VERSION);
+    buffer.extend_from_slice(&data.sample_rate.to_le_bytes());
+    buffer.extend_from_slice(&(data.start_sample as u64).to_le_bytes());
+    buffer.extend_from_slice(&(data.num_samples as u64).to_le_bytes());
+
+    match data.processing_mode {
+        Processing::Tick => buffer.extend_from_slice(&[0, 0]),
+        Processing::Batch(batch_size) => buffer.extend_from_slice(&[1, batch_size]),
+    }
+
+    buffer.extend_from_slice(&(data.inputs.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(&(data.outputs.len() as u32).to_le_bytes());
+
+    for channel in data.inputs.iter().chain(data.outputs.iter()) {
+        for sample in channel.iter().take(data.num_samples) {
+            buffer.extend_from_slice(&sample.to_le_bytes());
+        }
+    }
+
+    for channel in &data.abnormalities {
+        buffer.extend_from_slice(&(channel.len() as u32).to_le_bytes());
+        for (i, abnormality) in channel {
+            buffer.extend_from_slice(&(*i as u64).to_le_bytes());
+            buffer.push(match abnormality {
+                AbnormalSample::Nan => 0,
+                AbnormalSample::NegInf => 1,
+                AbnormalSample::PosInf => 2,
+            });
+        }
+    }
+
+    buffer
+}
+
+/// Decode data produced by [encode_raw]
+pub(crate) fn decode_raw(bytes: &[u8]) -> Result<SnapshotData, SnapshotError> {
+    let mut reader = Reader { bytes, pos: 0 };
+
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(SnapshotError::InvalidRawData("missing header".to_string()));
+    }
+    let version = reader.u8()?;
+    if version != VERSION {
+        return Err(SnapshotError::InvalidRawData(format!(
+            "unsupported format version [{version}]"
+        )));
+    }
+
+    let sample_rate = reader.f64()?;
+    let start_sample = reader.u64()? as usize;
+    let num_samples = reader.u64()? as usize;
+
+    let processing_mode = match (reader.u8()?, reader.u8()?) {
+        (0, _) => Processing::Tick,
+        (1, batch_size) => Processing::Batch(batch_size),
+        (tag, _) => {
+            return Err(SnapshotError::InvalidRawData(format!(
+                "unknown processing mode [{tag}]"
+            )));
+        }
+    };
+
+    let num_inputs = reader.u32()? as usize;
+    let num_outputs = reader.u32()? as usize;
+
+    let mut read_channels = |num_channels: usize| -> Result<Vec<Vec<f32>>, SnapshotError> {
+        (0..num_channels)
+            .map(|_| (0..num_samples).map(|_| reader.f32()).collect())
+            .collect()
+    };
+    let inputs = read_channels(num_inputs)?;
+    let outputs = read_channels(num_outputs)?;
+
+    let abnormalities = (0..num_outputs)
+        .map(|_| {
+            let count = reader.u32()? as usize;
+            (0..count)
+                .map(|_| {
+                    let i = reader.u64()? as usize;
+                    let abnormality = match reader.u8()? {
+                        0 => AbnormalSample::Nan,
+                        1 => AbnormalSample::NegInf,
+                        2 => AbnormalSample::PosInf,
+                        kind => {
+                            return Err(SnapshotError::InvalidRawData(format!(
+                                "unknown abnormal sample kind [{kind}]"
+                            )));
+                        }
+                    };
+                    Ok((i, abnormality))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if reader.pos != bytes.len() {
+        return Err(SnapshotError::InvalidRawData("trailing bytes".to_string()));
+    }
+
+    Ok(SnapshotData {
+        inputs,
+        outputs,
+        abnormalities,
+        num_samples,
+        sample_rate,
+        start_sample,
+        processing_mode,
+        frequency_responses: Vec::new(),
+    })
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SnapshotError> {
+        let end = self.pos + len;
+        if end > self.bytes.len() {
+            return Err(SnapshotError::InvalidRawData(
+                "unexpected end of data".to_string(),
+            ));
+        }
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SnapshotError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8, SnapshotError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, SnapshotError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, SnapshotError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn f32(&mut self) -> Result<f32, SnapshotError> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    fn f64(&mut self) -> Result<f64, SnapshotError> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+}
@@ -0,0 +1,228 @@
+use fundsp::audiounit::AudioUnit;
+use fundsp::fft::real_fft;
+use fundsp::math::Complex64;
+
+use crate::config::FrequencyResponseConfig;
+use crate::spectrum::fft_size;
+
+/// How a frequency response was obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseSource {
+    /// Reported by the unit itself via [AudioUnit::response]
+    Analytic,
+    /// Measured with an FFT of the unit's impulse response
+    ImpulseResponse,
+}
+
+/// Complex frequency response of one output channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputResponse {
+    /// Complex response at each frequency of [FrequencyResponse::frequencies]
+    pub response: Vec<Complex64>,
+    /// How the response was obtained
+    pub source: ResponseSource,
+}
+
+impl OutputResponse {
+    /// Magnitude in dB at each frequency
+    pub fn magnitude_db(&self) -> Vec<f64> {
+        self.response
+            .iter()
+            .map(|r| 20.0 * r.norm().max(1e-12).log10())
+            .collect()
+    }
+
+    /// Phase in degrees at each frequency, wrapped to `[-180..=180]`
+    pub fn phase_degrees(&self) -> Vec<f64> {
+        self.response.iter().map(|r| r.arg().to_degrees()).collect()
+    }
+
+    /// Phase in degrees at each frequency, unwrapped along the frequency grid
+    ///
+    /// Jumps over 180° between neighbouring frequencies are taken as wraps,
+    /// use a denser grid for units with long delays
+    pub fn unwrapped_phase_degrees(&self) -> Vec<f64> {
+        unwrap_phase(self.response.iter().map(|r| r.arg()))
+            .into_iter()
+            .map(f64::to_degrees)
+            .collect()
+    }
+
+    /// Group delay in samples at each of `frequencies`
+    ///
+    /// Negative derivative of unwrapped phase over angular frequency,
+    /// estimated with finite differences along the frequency grid
+    pub fn group_delay_samples(&self, frequencies: &[f64], sample_rate: f64) -> Vec<f64> {
+        let phase = unwrap_phase(self.response.iter().map(|r| r.arg()));
+        let omega: Vec<f64> = frequencies
+            .iter()
+            .map(|f| std::f64::consts::TAU * f / sample_rate)
+            .collect();
+        let last = phase.len().min(omega.len()).saturating_sub(1);
+
+        (0..=last)
+            .map(|i| {
+                if last == 0 {
+                    return 0.0;
+                }
+                let (a, b) = match i {
+                    0 => (0, 1),
+                    i if i == last => (last - 1, last),
+                    i => (i - 1, i + 1),
+                };
+                -(phase[b] - phase[a]) / (omega[b] - omega[a])
+            })
+            .collect()
+    }
+}
+
+/// Unwrap phase in radians, removing jumps of `2π`
+fn unwrap_phase(phase: impl Iterator<Item = f64>) -> Vec<f64> {
+    let mut unwrapped: Vec<f64> = Vec::new();
+    for value in phase {
+        let next = match unwrapped.last() {
+            Some(previous) => {
+                let delta = value - previous;
+                previous + delta - std::f64::consts::TAU * (delta / std::f64::consts::TAU).round()
+            }
+            None => value,
+        };
+        unwrapped.push(next);
+    }
+    unwrapped
+}
+
+/// Frequency response of every output of an audio unit
+///
+/// Captured by [crate::snapshot::capture_audio_unit] for output modes which plot it,
+/// or measured directly with [measure_frequency_response]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyResponse {
+    /// Configuration the response was measured with
+    pub config: FrequencyResponseConfig,
+    /// Sample rate the response was measured at
+    pub sample_rate: f64,
+    /// Log-spaced frequencies in Hz
+    pub frequencies: Vec<f64>,
+    /// Response per output channel
+    pub outputs: Vec<OutputResponse>,
+}
+
+/// Measure frequency response of every output of the `unit`
+///
+/// Uses the analytic [AudioUnit::response] where the unit provides one.
+/// Otherwise resets the unit, feeds a unit impulse to all inputs
+/// and takes an FFT of `config.impulse_length` output samples.
+///
+/// Unit's sample rate must be set beforehand.
+///
+/// ## Example
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let mut unit = lowpass_hz(1000.0, 0.7);
+/// unit.set_sample_rate(DEFAULT_SR);
+/// let response = measure_frequency_response(&mut unit, &FrequencyResponseConfig::default(), DEFAULT_SR);
+///
+/// assert_eq!(response.outputs[0].source, ResponseSource::Analytic);
+/// ```
+pub fn measure_frequency_response<N>(
+    unit: &mut N,
+    config: &FrequencyResponseConfig,
+    sample_rate: f64,
+) -> FrequencyResponse
+where
+    N: AudioUnit + ?Sized,
+{
+    let frequencies = config.frequencies(sample_rate);
+    let mut impulse_response: Option<Vec<Vec<Complex64>>> = None;
+
+    let outputs = (0..unit.outputs())
+        .map(|output| {
+            let analytic: Option<Vec<Complex64>> = frequencies
+                .iter()
+                .map(|&f| unit.response(output, f))
+                .collect();
+
+            match analytic {
+                Some(response) => OutputResponse {
+                    response,
+                    source: ResponseSource::Analytic,
+                },
+                None => {
+                    let spectra = impulse_response.get_or_insert_with(|| {
+                        impulse_response_spectra(unit, config.impulse_length)
+                    });
+                    let spectrum = &spectra[output];
+                    let fft_size = (spectrum.len() - 1) * 2;
+                    OutputResponse {
+                        response: frequencies
+                            .iter()
+                            .map(|&f| interpolate_bin(spectrum, f * fft_size as f64 / sample_rate))
+                            .collect(),
+                        source: ResponseSource::ImpulseResponse,
+                    }
+                }
+            }
+        })
+        .collect();
+
+    FrequencyResponse {
+        config: config.clone(),
+        sample_rate,
+        frequencies,
+        outputs,
+    }
+}
+
+/// Spectra (DC..=Nyquist) of impulse response of every output
+fn impulse_response_spectra<N>(unit: &mut N, impulse_length: usize) -> Vec<Vec<Complex64>>
+where
+    N: AudioUnit + ?Sized,
+{
+    let fft_size = fft_size(Some(impulse_length), impulse_length);
+    let mut responses = vec![vec![0.0f32; fft_size]; unit.outputs()];
+
+    unit.reset();
+    let mut input_frame = vec![1.0; unit.inputs()];
+    let mut output_frame = vec![0.0; unit.outputs()];
+    for i in 0..fft_size {
+        unit.tick(&input_frame, &mut output_frame);
+        for (response, &sample) in responses.iter_mut().zip(output_frame.iter()) {
+            response[i] = if sample.is_finite() { sample } else { 0.0 };
+        }
+        input_frame.fill(0.0);
+    }
+
+    responses
+        .iter_mut()
+        .map(|response| {
+            let bins = real_fft(response);
+            let nyquist = bins[0].im;
+            let mut spectrum: Vec<Complex64> = bins
+                .iter()
+                .enumerate()
+                .map(|(i, bin)| {
+                    if i == 0 {
+                        Complex64::new(bin.re as f64, 0.0)
+                    } else {
+                        Complex64::new(bin.re as f64, bin.im as f64)
+                    }
+                })
+                .collect();
+            spectrum.push(Complex64::new(nyquist as f64, 0.0));
+            spectrum
+        })
+        .collect()
+}
+
+/// Linearly interpolate spectrum at fractional bin `position`
+fn interpolate_bin(spectrum: &[Complex64], position: f64) -> Complex64 {
+    let last = spectrum.len() - 1;
+    let position = position.clamp(0.0, last as f64);
+    let i = (position.floor() as usize).min(last.saturating_sub(1));
+    let t = position - i as f64;
+    spectrum[i] * (1.0 - t) + spectrum[(i + 1).min(last)] * t
+}
@@ -0,0 +1,179 @@
+use std::ops::Range;
+
+use plotters::backend::SVGBackend;
+use plotters::drawing::IntoDrawingArea;
+use plotters::prelude::*;
+
+use crate::chart::{finish_svg, group_axis_color, layout_groups};
+use crate::chart_data::ChannelChartData;
+use crate::config::{BodeConfig, GroupDelayConfig, SvgChartConfig};
+use crate::response::FrequencyResponse;
+use crate::spectrum::log_frequency_chart;
+use crate::util::{get_contrasting_color, parse_hex_color};
+
+const DEFAULT_WIDTH: usize = 1000;
+
+/// Y axis range of response plot
+enum YRange {
+    /// Data range rounded to 10 dB, not lower than floor
+    Decibels {
+        floor: f64,
+    },
+    /// Data range with 10% padding
+    Auto,
+    Fixed(Range<f64>),
+}
+
+impl YRange {
+    fn resolve(&self, group: &[ChannelChartData]) -> Range<f64> {
+        let values = || group.iter().flat_map(|c| c.data.iter()).map(|v| *v as f64);
+        let min_val = values().fold(f64::INFINITY, f64::min);
+        let max_val = values().fold(f64::NEG_INFINITY, f64::max);
+
+        match self {
+            YRange::Decibels { floor } => {
+                let y_min = ((min_val / 10.0).floor() * 10.0).max(*floor);
+                let y_max = ((max_val / 10.0).ceil() * 10.0).max(y_min + 10.0);
+                y_min..y_max
+            }
+            YRange::Auto => {
+                let range = (max_val - min_val).max(f32::EPSILON as f64);
+                (min_val - range * 0.1)..(max_val + range * 0.1)
+            }
+            YRange::Fixed(range) => range.clone(),
+        }
+    }
+}
+
+/// One row of response chart, plotting one value per output against frequency
+struct ResponsePlot {
+    groups: Vec<Vec<ChannelChartData>>,
+    y_range: YRange,
+    y_desc: &'static str,
+}
+
+impl ResponsePlot {
+    fn new(
+        values: impl Iterator<Item = Vec<f64>>,
+        config: &SvgChartConfig,
+        y_range: YRange,
+        y_desc: &'static str,
+    ) -> Self {
+        let output_charts = values
+            .enumerate()
+            .map(|(i, values)| {
+                let values: Vec<f32> = values.into_iter().map(|v| v as f32).collect();
+                ChannelChartData::from_output_data(&values, &[], i, config)
+            })
+            .collect();
+        Self {
+            groups: layout_groups(config, vec![], output_charts),
+            y_range,
+            y_desc,
+        }
+    }
+}
+
+pub(crate) fn generate_bode_svg(response: &FrequencyResponse, config: &BodeConfig) -> String {
+    let magnitude = ResponsePlot::new(
+        response.outputs.iter().map(|output| {
+            output
+                .magnitude_db()
+                .into_iter()
+                .map(|db| db.max(config.min_db))
+                .collect()
+        }),
+        &config.chart,
+        YRange::Decibels {
+            floor: config.min_db,
+        },
+        "dB",
+    );
+    let phase = ResponsePlot::new(
+        response.outputs.iter().map(|output| output.phase_degrees()),
+        &config.chart,
+        YRange::Fixed(-180.0..180.0),
+        "°",
+    );
+
+    generate_response_svg(response, &config.chart, &[magnitude, phase])
+}
+
+pub(crate) fn generate_group_delay_svg(
+    response: &FrequencyResponse,
+    config: &GroupDelayConfig,
+) -> String {
+    let phase = ResponsePlot::new(
+        response
+            .outputs
+            .iter()
+            .map(|output| output.unwrapped_phase_degrees()),
+        &config.chart,
+        YRange::Auto,
+        "°",
+    );
+    let group_delay = ResponsePlot::new(
+        response
+            .outputs
+            .iter()
+            .map(|output| output.group_delay_samples(&response.frequencies, response.sample_rate)),
+        &config.chart,
+        YRange::Auto,
+        "samples",
+    );
+
+    generate_response_svg(response, &config.chart, &[phase, group_delay])
+}
+
+/// Plot rows of every chart group one under another
+fn generate_response_svg(
+    response: &FrequencyResponse,
+    chart_config: &SvgChartConfig,
+    plots: &[ResponsePlot],
+) -> String {
+    let num_groups = plots.first().map(|p| p.groups.len()).unwrap_or_default();
+
+    if num_groups == 0 || response.frequencies.is_empty() {
+        return "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\"><text>Empty</text></svg>".to_string();
+    }
+
+    let svg_width = chart_config.svg_width.unwrap_or(DEFAULT_WIDTH) as u32;
+    let total_height = (chart_config.svg_height_per_channel * num_groups) as u32;
+
+    let mut svg_buffer = String::new();
+    {
+        let root =
+            SVGBackend::with_string(&mut svg_buffer, (svg_width, total_height)).into_drawing_area();
+
+        let bg_color = parse_hex_color(&chart_config.background_color);
+        root.fill(&bg_color).unwrap();
+
+        let current_area = if let Some(ref title) = chart_config.chart_title {
+            let title_color = get_contrasting_color(&bg_color);
+            let text_style = TextStyle::from(("sans-serif", 20)).color(&title_color);
+            root.titled(title, text_style).unwrap()
+        } else {
+            root
+        };
+
+        let areas = current_area.split_evenly((num_groups * plots.len(), 1));
+        for (group_idx, group_areas) in areas.chunks(plots.len()).enumerate() {
+            for (plot, area) in plots.iter().zip(group_areas) {
+                let group = &plot.groups[group_idx];
+                log_frequency_chart(
+                    group,
+                    &response.frequencies,
+                    chart_config,
+                    group_axis_color(group),
+                    area,
+                    plot.y_range.resolve(group),
+                    plot.y_desc,
+                );
+            }
+        }
+
+        current_area.present().unwrap();
+    }
+
+    finish_svg(svg_buffer, chart_config, svg_width, total_height)
+}
@@ -0,0 +1,140 @@
+use plotters::coord::types::RangedCoordf64;
+use plotters::element::DashedPathElement;
+use plotters::prelude::*;
+
+use crate::config::SvgChartConfig;
+
+/// How samples of a waveform are drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SeriesStyle {
+    /// `Stems` when every sample gets at least 4 pixel columns, `Line` otherwise
+    Auto,
+    /// Continuous line through all samples
+    #[default]
+    Line,
+    /// Vertical stem from zero to each sample, topped with a dot
+    Stems,
+    /// Dot at each sample
+    Points,
+    /// Zero-order hold, each sample holds its value until the next one
+    Steps,
+}
+
+/// Fewest pixel columns per sample for which `SeriesStyle::Auto` draws stems
+const AUTO_STEMS_PIXELS_PER_SAMPLE: usize = 4;
+
+impl SeriesStyle {
+    /// Style used for `num_samples` plotted on `columns` pixel columns
+    pub(crate) fn resolve(&self, num_samples: usize, columns: usize) -> SeriesStyle {
+        match self {
+            SeriesStyle::Auto
+                if num_samples > 0 && num_samples * AUTO_STEMS_PIXELS_PER_SAMPLE <= columns =>
+            {
+                SeriesStyle::Stems
+            }
+            SeriesStyle::Auto => SeriesStyle::Line,
+            style => *style,
+        }
+    }
+}
+
+/// Draw `points` of one channel in resolved `style`
+///
+/// Dashed series draw dashed lines and hollow dots.
+/// Returns whether a legend entry was registered for `label`
+pub(crate) fn draw_waveform_series<DB: DrawingBackend>(
+    chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    points: Vec<(f64, f64)>,
+    style: SeriesStyle,
+    config: &SvgChartConfig,
+    color: RGBColor,
+    dashed: bool,
+    label: Option<&str>,
+) -> bool {
+    let line_style = ShapeStyle {
+        color: color.to_rgba(),
+        filled: false,
+        stroke_width: config.line_width as u32,
+    };
+    let dot_style = ShapeStyle {
+        filled: !dashed,
+        ..line_style
+    };
+    let radius = (config.line_width * 1.5).ceil().max(2.0) as u32;
+
+    let series = match style {
+        SeriesStyle::Stems | SeriesStyle::Points => {
+            if style == SeriesStyle::Stems {
+                // Stems grow from zero, or from the floor of magnitude scales
+                let y_range = chart.y_range();
+                let baseline = config
+                    .y_scale
+                    .floor()
+                    .unwrap_or(0.0)
+                    .clamp(y_range.start, y_range.end);
+                chart
+                    .draw_series(
+                        points
+                            .iter()
+                            .map(|&(x, y)| PathElement::new([(x, baseline), (x, y)], line_style)),
+                    )
+                    .unwrap();
+            }
+            chart
+                .draw_series(
+                    points
+                        .iter()
+                        .map(|&point| Circle::new(point, radius, dot_style)),
+                )
+                .unwrap()
+        }
+        SeriesStyle::Line | SeriesStyle::Steps | SeriesStyle::Auto => {
+            let points = if style == SeriesStyle::Steps {
+                steps(&points)
+            } else {
+                points
+            };
+            if dashed {
+                chart
+                    .draw_series(std::iter::once(DashedPathElement::new(
+                        points, 2, 3, line_style,
+                    )))
+                    .unwrap()
+            } else {
+                chart
+                    .draw_series(std::iter::once(PathElement::new(points, line_style)))
+                    .unwrap()
+            }
+        }
+    };
+
+    let Some(label) = label else {
+        return false;
+    };
+    let series = series.label(label);
+    match style {
+        SeriesStyle::Stems | SeriesStyle::Points => {
+            series.legend(move |(x, y)| Circle::new((x + 10, y), radius, dot_style));
+        }
+        _ if dashed => {
+            series.legend(move |(x, y)| {
+                DashedPathElement::new(vec![(x, y), (x + 20, y)], 2, 3, color)
+            });
+        }
+        _ => {
+            series.legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+        }
+    }
+    true
+}
+
+/// Zero-order hold path through `points`, the last one holds for one sample
+fn steps(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut steps = Vec::with_capacity(points.len() * 2);
+    for (i, &(x, y)) in points.iter().enumerate() {
+        let next_x = points.get(i + 1).map_or(x + 1.0, |next| next.0);
+        steps.push((x, y));
+        steps.push((next_x, y));
+    }
+    steps
+}
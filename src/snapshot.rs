@@ -1,10 +1,18 @@
 use fundsp::prelude::*;
 
 use crate::abnormal::AbnormalSample;
-use crate::chart::generate_svg;
-use crate::config::{Processing, SnapshotConfig};
+use crate::aliasing::{AliasMeasurement, AliasingSweep, sweep_frequencies};
+use crate::config::{
+    AliasingConfig, HarmonicsConfig, Processing, SnapshotConfig, SnapshotOutputMode,
+    TransferCurveConfig,
+};
+use crate::data::SnapshotData;
+use crate::error::SnapshotError;
+use crate::harmonics::HarmonicAnalysis;
 use crate::input::InputSource;
-use crate::wav::generate_wav;
+use crate::loudness::Loudness;
+use crate::response::{FrequencyResponse, measure_frequency_response};
+use crate::warmup::WarmUp;
 
 /// Create a snapshot of audio unit outputs (default: SVG; configure `output_mode` for WAV)
 /// ## Example
@@ -70,6 +78,8 @@ where
 
 /// Create a snapshot (inputs & outputs) with options (choose SVG chart or WAV via `output_mode`)
 ///
+/// Panics on any [SnapshotError], see [try_snapshot_audio_unit_with_input_and_options]
+///
 /// ## Example
 ///
 /// ```
@@ -82,27 +92,485 @@ where
 /// println!("{}", svg.len());
 /// ```
 pub fn snapshot_audio_unit_with_input_and_options<N>(
-    mut unit: N,
-    mut input_source: InputSource,
+    unit: N,
+    input_source: InputSource,
     config: SnapshotConfig,
 ) -> Vec<u8>
+where
+    N: AudioUnit,
+{
+    try_snapshot_audio_unit_with_input_and_options(unit, input_source, config)
+        .unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Fallible version of [snapshot_audio_unit]
+///
+/// ## Example
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let unit = sine_hz::<f32>(440.0);
+/// let svg = try_snapshot_audio_unit(unit).unwrap();
+/// println!("{}", svg.len());
+/// ```
+pub fn try_snapshot_audio_unit<N>(unit: N) -> Result<Vec<u8>, SnapshotError>
+where
+    N: AudioUnit,
+{
+    try_snapshot_audio_unit_with_input_and_options(
+        unit,
+        InputSource::None,
+        SnapshotConfig::default(),
+    )
+}
+
+/// Fallible version of [snapshot_audio_unit_with_options]
+///
+/// ## Example
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let config = SnapshotConfig {
+///     processing_mode: Processing::Batch(128),
+///     ..SnapshotConfig::default()
+/// };
+/// let result = try_snapshot_audio_unit_with_options(sine_hz::<f32>(440.0), config);
+/// assert!(matches!(result, Err(SnapshotError::InvalidBatchSize { .. })));
+/// ```
+pub fn try_snapshot_audio_unit_with_options<N>(
+    unit: N,
+    options: SnapshotConfig,
+) -> Result<Vec<u8>, SnapshotError>
+where
+    N: AudioUnit,
+{
+    try_snapshot_audio_unit_with_input_and_options(unit, InputSource::None, options)
+}
+
+/// Fallible version of [snapshot_audio_unit_with_input]
+///
+/// ## Example
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let result = try_snapshot_audio_unit_with_input(
+///     lowpass_hz(1000.0, 1.0),
+///     InputSource::Flat(vec![0.0, 0.0]),
+/// );
+/// assert!(matches!(
+///     result,
+///     Err(SnapshotError::InputChannelMismatch { expected: 1, actual: 2 })
+/// ));
+/// ```
+pub fn try_snapshot_audio_unit_with_input<N>(
+    unit: N,
+    input_source: InputSource,
+) -> Result<Vec<u8>, SnapshotError>
+where
+    N: AudioUnit,
+{
+    try_snapshot_audio_unit_with_input_and_options(unit, input_source, SnapshotConfig::default())
+}
+
+/// Fallible version of [snapshot_audio_unit_with_input_and_options]
+///
+/// Returns an error instead of panicking when:
+/// - the unit produces abnormal samples and `allow_abnormal_samples` is `false`
+/// - the input source (or warm-up input) doesn't match the unit's inputs or `num_samples`
+/// - `Processing::Batch` size is out of range
+/// - loudness of outputs exceeds `loudness_limits`
+/// - WAV data can not be written
+///
+/// ## Example
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let result = try_snapshot_audio_unit_with_input_and_options(
+///     pass(),
+///     InputSource::Flat(vec![f32::NAN]),
+///     SnapshotConfig::default(),
+/// );
+/// assert!(matches!(
+///     result,
+///     Err(SnapshotError::AbnormalSample { channel: 0, sample: 0, .. })
+/// ));
+/// ```
+pub fn try_snapshot_audio_unit_with_input_and_options<N>(
+    unit: N,
+    input_source: InputSource,
+    config: SnapshotConfig,
+) -> Result<Vec<u8>, SnapshotError>
+where
+    N: AudioUnit,
+{
+    let data = try_capture_audio_unit(unit, input_source, &config)?;
+
+    data.try_render(&config.output_mode)
+}
+
+/// Create snapshots for every output mode of the config from one processing run
+///
+/// Returns snapshot bytes in the order of [SnapshotConfig::output_modes]
+///
+/// Panics on any [SnapshotError], see [try_snapshot_audio_unit_all_output_modes]
+///
+/// ## Example
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let config = SnapshotConfigBuilder::default()
+///     .extra_output_mode(WavOutput::Wav16)
+///     .build()
+///     .unwrap();
+/// let unit = sine_hz::<f32>(440.0);
+/// let snapshots = snapshot_audio_unit_all_output_modes(unit, InputSource::None, config);
+/// assert_eq!(snapshots.len(), 2);
+/// ```
+pub fn snapshot_audio_unit_all_output_modes<N>(
+    unit: N,
+    input_source: InputSource,
+    config: SnapshotConfig,
+) -> Vec<Vec<u8>>
+where
+    N: AudioUnit,
+{
+    try_snapshot_audio_unit_all_output_modes(unit, input_source, config)
+        .unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Fallible version of [snapshot_audio_unit_all_output_modes]
+pub fn try_snapshot_audio_unit_all_output_modes<N>(
+    unit: N,
+    input_source: InputSource,
+    config: SnapshotConfig,
+) -> Result<Vec<Vec<u8>>, SnapshotError>
+where
+    N: AudioUnit,
+{
+    let data = try_capture_audio_unit(unit, input_source, &config)?;
+
+    config
+        .output_modes()
+        .map(|output_mode| data.try_render(output_mode))
+        .collect()
+}
+
+/// Create a transfer curve snapshot (SVG) of a static waveshaper
+///
+/// Feeds a ramp from `min_input` to `max_input` to every input of the unit
+/// and plots each output against it, with optional identity line and slope.
+///
+/// Panics on any [SnapshotError], see [try_snapshot_transfer_curve]
+///
+/// ## Example
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let config = TransferCurveConfigBuilder::default()
+///     .show_derivative(true)
+///     .build()
+///     .unwrap();
+/// let svg = snapshot_transfer_curve(shape(Tanh(2.0)), config);
+/// println!("{}", svg.len());
+/// ```
+pub fn snapshot_transfer_curve<N>(unit: N, config: TransferCurveConfig) -> Vec<u8>
+where
+    N: AudioUnit,
+{
+    try_snapshot_transfer_curve(unit, config).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Fallible version of [snapshot_transfer_curve]
+///
+/// Returns an error when the unit produces abnormal samples within the ramp
+pub fn try_snapshot_transfer_curve<N>(
+    unit: N,
+    config: TransferCurveConfig,
+) -> Result<Vec<u8>, SnapshotError>
+where
+    N: AudioUnit,
+{
+    let snapshot_config = SnapshotConfig {
+        num_samples: config.num_points,
+        ..SnapshotConfig::default()
+    };
+    let input_source = InputSource::ramp(config.min_input, config.max_input, config.num_points);
+    let data = try_capture_audio_unit(unit, input_source, &snapshot_config)?;
+
+    Ok(data.render_transfer_curve(&config))
+}
+
+/// Create a harmonic distortion snapshot (SVG): bar chart of harmonic levels of every output
+/// with THD and THD+N in the legend
+///
+/// Units with inputs are driven by a full scale sine at `frequency`, scale the drive inside
+/// the unit, e.g. `mul(0.5) >> shape(...)`. Units without inputs are analysed free-running.
+///
+/// Panics on any [SnapshotError], see [try_snapshot_harmonics]
+///
+/// ## Example
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let config = HarmonicsConfigBuilder::default()
+///     .frequency(440.0)
+///     .build()
+///     .unwrap();
+/// let svg = snapshot_harmonics(saw_hz(440.0), config);
+/// println!("{}", svg.len());
+/// ```
+pub fn snapshot_harmonics<N>(unit: N, config: HarmonicsConfig) -> Vec<u8>
+where
+    N: AudioUnit,
+{
+    try_snapshot_harmonics(unit, config).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Fallible version of [snapshot_harmonics]
+pub fn try_snapshot_harmonics<N>(unit: N, config: HarmonicsConfig) -> Result<Vec<u8>, SnapshotError>
+where
+    N: AudioUnit,
+{
+    let data = try_capture_sine_driven(
+        unit,
+        config.frequency,
+        config.num_samples,
+        config.sample_rate,
+        &config.warm_up,
+    )?;
+
+    Ok(data.render_harmonics(&config))
+}
+
+/// Analyse harmonic distortion of every output, for assertions
+///
+/// Captures the unit like [snapshot_harmonics]
+///
+/// Panics on any [SnapshotError], see [try_analyze_harmonics]
+pub fn analyze_harmonics<N>(unit: N, config: &HarmonicsConfig) -> Vec<HarmonicAnalysis>
+where
+    N: AudioUnit,
+{
+    try_analyze_harmonics(unit, config).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Fallible version of [analyze_harmonics]
+pub fn try_analyze_harmonics<N>(
+    unit: N,
+    config: &HarmonicsConfig,
+) -> Result<Vec<HarmonicAnalysis>, SnapshotError>
+where
+    N: AudioUnit,
+{
+    let data = try_capture_sine_driven(
+        unit,
+        config.frequency,
+        config.num_samples,
+        config.sample_rate,
+        &config.warm_up,
+    )?;
+
+    Ok(data.harmonic_analysis(config))
+}
+
+/// Capture unit driven by a sine at `frequency`, or free-running without inputs
+fn try_capture_sine_driven<N>(
+    unit: N,
+    frequency: f64,
+    num_samples: usize,
+    sample_rate: f64,
+    warm_up: &WarmUp,
+) -> Result<SnapshotData, SnapshotError>
+where
+    N: AudioUnit,
+{
+    let snapshot_config = SnapshotConfig {
+        num_samples,
+        sample_rate,
+        warm_up: warm_up.clone(),
+        ..SnapshotConfig::default()
+    };
+    let input_source = if unit.inputs() > 0 {
+        InputSource::sine(frequency as f32, sample_rate as f32)
+    } else {
+        InputSource::None
+    };
+
+    try_capture_audio_unit(unit, input_source, &snapshot_config)
+}
+
+/// Create an aliasing snapshot (SVG): alias level of every output versus fundamental frequency
+///
+/// `make_unit` builds the unit for each swept frequency in Hz, e.g. `|f| saw_hz(f)`.
+/// Units with inputs are driven by a full scale sine at that frequency,
+/// e.g. `|_| shape(Tanh(4.0))`, units without inputs are analysed free-running.
+///
+/// Panics on any [SnapshotError], see [try_snapshot_aliasing]
+///
+/// ## Example
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let config = AliasingConfigBuilder::default()
+///     .num_frequencies(4)
+///     .max_alias_level(-60.0)
+///     .build()
+///     .unwrap();
+/// let svg = snapshot_aliasing(|frequency| square_hz(frequency), config);
+/// println!("{}", svg.len());
+/// ```
+pub fn snapshot_aliasing<N, F>(make_unit: F, config: AliasingConfig) -> Vec<u8>
+where
+    N: AudioUnit,
+    F: FnMut(f32) -> N,
+{
+    try_snapshot_aliasing(make_unit, config).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Fallible version of [snapshot_aliasing]
+///
+/// Returns [SnapshotError::AliasingLimitExceeded] when an alias level
+/// exceeds `max_alias_level` of the config
+pub fn try_snapshot_aliasing<N, F>(
+    make_unit: F,
+    config: AliasingConfig,
+) -> Result<Vec<u8>, SnapshotError>
+where
+    N: AudioUnit,
+    F: FnMut(f32) -> N,
+{
+    let sweep = try_measure_aliasing(make_unit, &config)?;
+    if let Some(limit) = config.max_alias_level {
+        sweep.check(limit)?;
+    }
+
+    Ok(sweep.render_svg(&config))
+}
+
+/// Measure aliasing of every output across the swept frequencies, for assertions
+///
+/// Sweeps like [snapshot_aliasing] without checking `max_alias_level`
+///
+/// Panics on any [SnapshotError], see [try_measure_aliasing]
+pub fn measure_aliasing<N, F>(make_unit: F, config: &AliasingConfig) -> AliasingSweep
+where
+    N: AudioUnit,
+    F: FnMut(f32) -> N,
+{
+    try_measure_aliasing(make_unit, config).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Fallible version of [measure_aliasing]
+pub fn try_measure_aliasing<N, F>(
+    mut make_unit: F,
+    config: &AliasingConfig,
+) -> Result<AliasingSweep, SnapshotError>
+where
+    N: AudioUnit,
+    F: FnMut(f32) -> N,
+{
+    let mut outputs: Vec<Vec<AliasMeasurement>> = vec![];
+    for frequency in sweep_frequencies(config) {
+        let data = try_capture_sine_driven(
+            make_unit(frequency as f32),
+            frequency,
+            config.num_samples,
+            config.sample_rate,
+            &config.warm_up,
+        )?;
+        outputs.resize_with(data.outputs.len(), Vec::new);
+        for (measurements, samples) in outputs.iter_mut().zip(&data.outputs) {
+            measurements.push(AliasMeasurement::measure(
+                samples,
+                frequency,
+                data.sample_rate,
+                config,
+            ));
+        }
+    }
+
+    Ok(AliasingSweep { outputs })
+}
+
+/// Process audio unit and capture its raw inputs and outputs without rendering
+///
+/// Panics on any [SnapshotError], see [try_capture_audio_unit]
+///
+/// ## Example
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let config = SnapshotConfigBuilder::default()
+///     .num_samples(64)
+///     .build()
+///     .unwrap();
+/// let data = capture_audio_unit(lowpass_hz(1000.0, 1.0), InputSource::impulse(), &config);
+/// assert_eq!(data.outputs[0].len(), 64);
+///
+/// let svg = data.render_svg(&SvgChartConfig::default());
+/// let wav = data.render_wav(&WavOutput::Wav16);
+/// println!("{} {}", svg.len(), wav.len());
+/// ```
+pub fn capture_audio_unit<N>(
+    unit: N,
+    input_source: InputSource,
+    config: &SnapshotConfig,
+) -> SnapshotData
+where
+    N: AudioUnit,
+{
+    try_capture_audio_unit(unit, input_source, config).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Fallible version of [capture_audio_unit]
+///
+/// `output_mode` of the config is ignored, render the returned data with [SnapshotData::try_render]
+pub fn try_capture_audio_unit<N>(
+    mut unit: N,
+    mut input_source: InputSource,
+    config: &SnapshotConfig,
+) -> Result<SnapshotData, SnapshotError>
 where
     N: AudioUnit,
 {
     let num_inputs = N::inputs(&unit);
     let num_outputs = N::outputs(&unit);
 
+    if let Processing::Batch(batch_size) = config.processing_mode
+        && (batch_size == 0 || batch_size as usize > MAX_BUFFER_SIZE)
+    {
+        return Err(SnapshotError::InvalidBatchSize { batch_size });
+    }
+
     unit.set_sample_rate(config.sample_rate);
     unit.reset();
     unit.allocate();
 
-    let input_data = input_source.make_data(num_inputs, config.num_samples);
+    let input_data = input_source.try_make_data(num_inputs, config.num_samples)?;
 
     let mut output_data: Vec<Vec<f32>> = vec![vec![]; num_outputs];
 
     let warmup_samples = config
         .warm_up
-        .warm_up_samples(config.sample_rate, num_inputs);
+        .warm_up_samples(config.sample_rate, num_inputs)?;
 
     let num_warmup_samples = warmup_samples
         .iter()
@@ -112,18 +580,23 @@ where
 
     let mut abnormalities: Vec<Vec<(usize, AbnormalSample)>> = vec![vec![]; num_outputs];
 
-    let mut checked_sample = |mut sample: f32, ch: usize, i: usize| {
+    let mut checked_sample = |sample: f32, ch: usize, i: usize| {
         if sample.is_nan() || sample.is_infinite() {
             let abnormality = AbnormalSample::from(sample);
 
             if config.allow_abnormal_samples {
                 abnormalities[ch].push((i, abnormality));
-                sample = 0.0;
+                Ok(0.0)
             } else {
-                panic!("Output channel #[{ch}] at sample [{i}] produced [{abnormality}] sample");
+                Err(SnapshotError::AbnormalSample {
+                    channel: ch,
+                    sample: i,
+                    kind: abnormality,
+                })
             }
+        } else {
+            Ok(sample)
         }
-        sample
     };
 
     (0..num_warmup_samples).for_each(|i| {
@@ -138,25 +611,17 @@ where
 
     match config.processing_mode {
         Processing::Tick => {
-            (0..config.num_samples).for_each(|i| {
-                let mut input_frame = vec![0.0; num_inputs];
-                for ch in 0..num_inputs {
-                    input_frame[ch] = input_data[ch][i];
-                }
+            for i in 0..config.num_samples {
+                let input_frame: Vec<f32> = input_data.iter().map(|input| input[i]).collect();
                 let mut output_frame = vec![0.0; num_outputs];
                 unit.tick(&input_frame, &mut output_frame);
-                for ch in 0..num_outputs {
-                    let sample = checked_sample(output_frame[ch], ch, i);
+                for (ch, &sample) in output_frame.iter().enumerate() {
+                    let sample = checked_sample(sample, ch, i)?;
                     output_data[ch].push(sample);
                 }
-            });
+            }
         }
         Processing::Batch(batch_size) => {
-            assert!(
-                batch_size <= MAX_BUFFER_SIZE as u8,
-                "Batch size must be less than or equal to [{MAX_BUFFER_SIZE}]"
-            );
-
             let samples_index = (0..config.num_samples).collect::<Vec<_>>();
             for chunk in samples_index.chunks(batch_size as usize) {
                 let mut input_buff = BufferVec::new(num_inputs);
@@ -173,39 +638,44 @@ where
                 unit.process(chunk.len(), &input_ref, &mut output_ref);
 
                 for (ch, data) in output_data.iter_mut().enumerate() {
-                    data.extend(
-                        output_buf
-                            .channel_f32(ch)
-                            .iter()
-                            .enumerate()
-                            .map(|(i, &value)| checked_sample(value, ch, i + chunk[0])),
-                    );
+                    let samples = output_buf.channel_f32(ch).iter().take(chunk.len());
+                    for (i, &value) in samples.enumerate() {
+                        data.push(checked_sample(value, ch, i + chunk[0])?);
+                    }
                 }
             }
         }
     }
 
-    match config.output_mode {
-        crate::config::SnapshotOutputMode::SvgChart(svg_chart_config) => {
-            let start_sample = config.warm_up.num_samples(config.sample_rate);
+    if let Some(limits) = &config.loudness_limits {
+        Loudness::measure(&output_data, config.sample_rate).check(limits)?;
+    }
 
-            generate_svg(
-                &input_data,
-                &output_data,
-                &abnormalities,
-                &svg_chart_config,
+    let mut frequency_responses: Vec<FrequencyResponse> = vec![];
+    for response_config in config
+        .output_modes()
+        .filter_map(SnapshotOutputMode::frequency_response_config)
+    {
+        if !frequency_responses
+            .iter()
+            .any(|response| response.config == *response_config)
+        {
+            frequency_responses.push(measure_frequency_response(
+                &mut unit,
+                response_config,
                 config.sample_rate,
-                config.num_samples,
-                start_sample,
-            )
-            .as_bytes()
-            .to_vec()
+            ));
         }
-        crate::config::SnapshotOutputMode::Wav(wav_output) => generate_wav(
-            &output_data,
-            &wav_output,
-            config.sample_rate,
-            config.num_samples,
-        ),
     }
+
+    Ok(SnapshotData {
+        inputs: input_data,
+        outputs: output_data,
+        abnormalities,
+        num_samples: config.num_samples,
+        sample_rate: config.sample_rate,
+        start_sample: config.warm_up.num_samples(config.sample_rate),
+        processing_mode: config.processing_mode,
+        frequency_responses,
+    })
 }
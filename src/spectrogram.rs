@@ -0,0 +1,378 @@
+use plotters::backend::SVGBackend;
+use plotters::drawing::IntoDrawingArea;
+use plotters::prelude::*;
+
+use crate::chart::finish_svg;
+use crate::chart_data::ChannelChartData;
+use crate::config::SpectrogramConfig;
+use crate::data::SnapshotData;
+use crate::spectrum::{amplitude_db, bin_frequency, fft_size, frame_magnitudes};
+use crate::util::{
+    frequency_formatter, get_contrasting_color, num_x_labels, parse_hex_color, time_formatter,
+};
+
+const DEFAULT_WIDTH: usize = 1000;
+const COLOR_BAR_WIDTH: u32 = 70;
+/// Number of distinct heat map colors, each step of the color bar
+const COLOR_STEPS: usize = 64;
+
+/// Color map of spectrogram heat map, from quiet to loud
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMap {
+    /// Perceptually uniform dark blue to yellow
+    #[default]
+    Viridis,
+    /// Perceptually uniform black to light yellow through purple
+    Magma,
+    /// Perceptually uniform black to light yellow through red
+    Inferno,
+    /// Black to white
+    Grayscale,
+}
+
+impl ColorMap {
+    pub(crate) fn stops(&self) -> &'static [&'static str] {
+        match self {
+            ColorMap::Viridis => &[
+                "#440154", "#482878", "#3E4A89", "#31688E", "#26828E", "#1F9E89", "#35B779",
+                "#6DCD59", "#B4DE2C", "#FDE725",
+            ],
+            ColorMap::Magma => &[
+                "#000004", "#1C1044", "#4F127B", "#812581", "#B5367A", "#E55064", "#FB8761",
+                "#FEC287", "#FCFDBF",
+            ],
+            ColorMap::Inferno => &[
+                "#000004", "#1F0C48", "#550F6D", "#88226A", "#BA3655", "#E35933", "#F98C0A",
+                "#F9C932", "#FCFFA4",
+            ],
+            ColorMap::Grayscale => &["#000000", "#FFFFFF"],
+        }
+    }
+
+    /// Color of `value` in range `[0.0..=1.0]`
+    pub(crate) fn color(&self, value: f64) -> RGBColor {
+        let stops = self.stops();
+        let position = value.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+        let i = (position.floor() as usize).min(stops.len() - 2);
+        let t = position - i as f64;
+
+        let from = parse_hex_color(stops[i]);
+        let to = parse_hex_color(stops[i + 1]);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        RGBColor(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+    }
+}
+
+/// Short-time spectrum of one channel
+struct Stft {
+    /// Plotted FFT bins
+    bins: std::ops::Range<usize>,
+    fft_size: usize,
+    /// Number of samples between consecutive frames
+    hop: usize,
+    /// Sample index of each frame's center
+    centers: Vec<usize>,
+    /// Magnitude in dB per frame per plotted bin
+    frames: Vec<Vec<f32>>,
+}
+
+fn stft(
+    samples: &[f32],
+    config: &SpectrogramConfig,
+    fft_size: usize,
+    bins: &std::ops::Range<usize>,
+) -> Stft {
+    let frame_size = samples.len().min(fft_size);
+    let window = config.window.coefficients(frame_size);
+    let hop = config.hop_size.unwrap_or(fft_size / 4).max(1);
+
+    let mut centers = Vec::new();
+    let mut frames = Vec::new();
+    let mut start = 0;
+    while start + frame_size <= samples.len() && frame_size > 0 {
+        let magnitudes = frame_magnitudes(&samples[start..start + frame_size], &window, fft_size);
+        frames.push(
+            magnitudes[bins.clone()]
+                .iter()
+                .map(|m| amplitude_db(*m, config.min_db))
+                .collect(),
+        );
+        centers.push(start + frame_size / 2);
+        start += hop;
+    }
+
+    Stft {
+        bins: bins.clone(),
+        fft_size,
+        hop,
+        centers,
+        frames,
+    }
+}
+
+pub(crate) fn generate_spectrogram_svg(data: &SnapshotData, config: &SpectrogramConfig) -> String {
+    let chart_config = &config.chart;
+    let num_inputs = if chart_config.with_inputs {
+        data.inputs.len()
+    } else {
+        0
+    };
+    let num_channels = data.outputs.len() + num_inputs;
+
+    if data.num_samples == 0 || num_channels == 0 {
+        return "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\"><text>Empty</text></svg>".to_string();
+    }
+
+    let fft_size = fft_size(Some(config.fft_size), data.num_samples);
+    let nyquist = data.sample_rate / 2.0;
+    let max_frequency = config.max_frequency.unwrap_or(nyquist).min(nyquist);
+    let min_frequency = config
+        .min_frequency
+        .max(bin_frequency(1, fft_size, data.sample_rate))
+        .min(max_frequency / 2.0);
+
+    // Plotted bins, each spanning half a bin to either side of its frequency
+    let first_bin = (1..=fft_size / 2)
+        .find(|bin| bin_frequency(*bin, fft_size, data.sample_rate) >= min_frequency)
+        .unwrap_or(1);
+    let last_bin = (first_bin..=fft_size / 2)
+        .rev()
+        .find(|bin| bin_frequency(*bin, fft_size, data.sample_rate) <= max_frequency)
+        .unwrap_or(first_bin);
+    let bins = first_bin..last_bin + 1;
+
+    let channels: Vec<(ChannelChartData, Stft)> = data
+        .inputs
+        .iter()
+        .take(num_inputs)
+        .enumerate()
+        .map(|(i, samples)| {
+            (
+                ChannelChartData::from_input_data(&[], i, chart_config),
+                stft(samples, config, fft_size, &bins),
+            )
+        })
+        .chain(data.outputs.iter().enumerate().map(|(i, samples)| {
+            (
+                ChannelChartData::from_output_data(&[], &[], i, chart_config),
+                stft(samples, config, fft_size, &bins),
+            )
+        }))
+        .collect();
+
+    let svg_width = chart_config.svg_width.unwrap_or(DEFAULT_WIDTH) as u32;
+    let total_height = (chart_config.svg_height_per_channel * num_channels) as u32;
+
+    let mut svg_buffer = String::new();
+    {
+        let root =
+            SVGBackend::with_string(&mut svg_buffer, (svg_width, total_height)).into_drawing_area();
+
+        let bg_color = parse_hex_color(&chart_config.background_color);
+        root.fill(&bg_color).unwrap();
+
+        let current_area = if let Some(ref title) = chart_config.chart_title {
+            let title_color = get_contrasting_color(&bg_color);
+            let text_style = TextStyle::from(("sans-serif", 20)).color(&title_color);
+            root.titled(title, text_style).unwrap()
+        } else {
+            root
+        };
+
+        let max_db = channels
+            .iter()
+            .flat_map(|(_, stft)| stft.frames.iter().flatten())
+            .cloned()
+            .fold(config.min_db, f32::max);
+        let db_range =
+            config.min_db as f64..((max_db / 10.0).ceil() * 10.0).max(config.min_db + 10.0) as f64;
+
+        let areas = current_area.split_evenly((num_channels, 1));
+        for ((channel, stft), area) in channels.iter().zip(areas) {
+            let (heat_map_area, color_bar_area) =
+                area.split_horizontally(svg_width.saturating_sub(COLOR_BAR_WIDTH));
+
+            heat_map_chart(
+                channel,
+                stft,
+                data,
+                config,
+                db_range.clone(),
+                &heat_map_area,
+            );
+            color_bar(config, channel.color, db_range.clone(), &color_bar_area);
+        }
+
+        current_area.present().unwrap();
+    }
+
+    finish_svg(svg_buffer, chart_config, svg_width, total_height)
+}
+
+fn heat_map_chart<DB: DrawingBackend>(
+    channel: &ChannelChartData,
+    stft: &Stft,
+    data: &SnapshotData,
+    config: &SpectrogramConfig,
+    db_range: std::ops::Range<f64>,
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+) {
+    let chart_config = &config.chart;
+    let start_from = data.start_sample;
+    let bins = &stft.bins;
+    let bin_edge = |bin: f64| bin * data.sample_rate / stft.fft_size as f64;
+    let min_frequency = bin_edge(bins.start as f64 - 0.5);
+    let max_frequency = bin_edge(bins.end as f64 - 0.5);
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(5)
+        .x_label_area_size(if chart_config.show_labels { 35 } else { 0 })
+        .y_label_area_size(if chart_config.show_labels { 50 } else { 0 })
+        .build_cartesian_2d(
+            start_from as f64..(data.num_samples + start_from) as f64,
+            (min_frequency..max_frequency).log_scale(),
+        )
+        .unwrap();
+
+    let mut mesh = chart.configure_mesh();
+
+    mesh.axis_style(channel.color.mix(0.3));
+    mesh.disable_mesh();
+
+    if let Some(label) = &channel.label {
+        let x_labels = num_x_labels(data.num_samples, data.sample_rate);
+        mesh.x_labels(
+            chart_config
+                .max_labels_x_axis
+                .map(|mx| x_labels.min(mx))
+                .unwrap_or(x_labels),
+        )
+        .y_labels(10)
+        .y_desc("Hz")
+        .x_desc(label)
+        .label_style(("sans-serif", 10, &channel.color));
+    }
+
+    let formatter = |v: &f64| time_formatter(*v as usize, data.sample_rate);
+    if chart_config.format_x_axis_labels_as_time {
+        mesh.x_label_formatter(&formatter);
+    }
+    let y_formatter = |v: &f64| frequency_formatter(*v);
+    mesh.y_label_formatter(&y_formatter);
+
+    mesh.draw().unwrap();
+
+    let hop = stft.hop as f64;
+    let db_span = (db_range.end - db_range.start).max(f64::EPSILON);
+    let last_sample = (data.num_samples + start_from) as f64;
+    let pixel_x = |x: f64| chart.backend_coord(&(x, min_frequency)).0;
+    let pixel_y = |frequency: f64| chart.backend_coord(&(start_from as f64, frequency)).1;
+
+    // Frames starting on the same pixel column merge into one column, keeping the loudest bins
+    let mut columns: Vec<(f64, f64, Vec<f32>)> = Vec::new();
+    for (center, frame) in stft.centers.iter().zip(stft.frames.iter()) {
+        let center = (center + start_from) as f64;
+        let x0 = (center - hop / 2.0).max(start_from as f64);
+        let x1 = (center + hop / 2.0).min(last_sample);
+        match columns.last_mut() {
+            Some((column_x0, column_x1, column)) if pixel_x(*column_x0) == pixel_x(x0) => {
+                *column_x1 = x1;
+                column
+                    .iter_mut()
+                    .zip(frame)
+                    .for_each(|(a, b)| *a = a.max(*b));
+            }
+            _ => columns.push((x0, x1, frame.clone())),
+        }
+    }
+
+    // Bins starting on the same pixel row merge into one row
+    let mut rows: Vec<(std::ops::Range<usize>, f64, f64)> = Vec::new();
+    for (i, bin) in bins.clone().enumerate() {
+        let y0 = bin_edge(bin as f64 - 0.5);
+        let y1 = bin_edge(bin as f64 + 0.5);
+        match rows.last_mut() {
+            Some((indices, row_y0, row_y1)) if pixel_y(*row_y0) == pixel_y(y0) => {
+                indices.end = i + 1;
+                *row_y1 = y1;
+            }
+            _ => rows.push((i..i + 1, y0, y1)),
+        }
+    }
+
+    // Adjacent rows of the same color within a column are drawn as one rectangle
+    let mut cells = Vec::new();
+    for (x0, x1, column) in &columns {
+        let mut run: Option<(RGBColor, f64, f64)> = None;
+        for (indices, y0, y1) in &rows {
+            let db = column[indices.clone()]
+                .iter()
+                .cloned()
+                .fold(f32::NEG_INFINITY, f32::max);
+            // Same color steps as the color bar, so neighbouring cells share colors
+            let step = ((db as f64 - db_range.start) / db_span * COLOR_STEPS as f64)
+                .floor()
+                .clamp(0.0, COLOR_STEPS as f64 - 1.0);
+            let color = config.color_map.color((step + 0.5) / COLOR_STEPS as f64);
+            match &mut run {
+                Some((run_color, _, run_y1)) if *run_color == color => *run_y1 = *y1,
+                _ => {
+                    cells.extend(run.replace((color, *y0, *y1)).map(|cell| (*x0, *x1, cell)));
+                }
+            }
+        }
+        cells.extend(run.map(|cell| (*x0, *x1, cell)));
+    }
+
+    chart
+        .draw_series(
+            cells.into_iter().map(|(x0, x1, (color, y0, y1))| {
+                Rectangle::new([(x0, y0), (x1, y1)], color.filled())
+            }),
+        )
+        .unwrap();
+}
+
+/// Vertical scale of heat map colors in dB
+fn color_bar<DB: DrawingBackend>(
+    config: &SpectrogramConfig,
+    axis_color: RGBColor,
+    db_range: std::ops::Range<f64>,
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+) {
+    let chart_config = &config.chart;
+    let mut chart = ChartBuilder::on(area)
+        .margin(5)
+        .margin_bottom(if chart_config.show_labels { 40 } else { 5 })
+        .y_label_area_size(if chart_config.show_labels { 40 } else { 0 })
+        .build_cartesian_2d(0.0..1.0, db_range.clone())
+        .unwrap();
+
+    let mut mesh = chart.configure_mesh();
+    mesh.disable_mesh()
+        .disable_x_axis()
+        .axis_style(axis_color.mix(0.3));
+    let y_formatter = |v: &f64| format!("{v:.0}");
+    if chart_config.show_labels {
+        mesh.y_labels(5)
+            .y_label_formatter(&y_formatter)
+            .y_desc("dB")
+            .label_style(("sans-serif", 10, &axis_color));
+    }
+    mesh.draw().unwrap();
+
+    let steps = COLOR_STEPS;
+    let step = (db_range.end - db_range.start) / steps as f64;
+    chart
+        .draw_series((0..steps).map(|i| {
+            let db = db_range.start + i as f64 * step;
+            Rectangle::new(
+                [(0.0, db), (1.0, db + step)],
+                config
+                    .color_map
+                    .color((i as f64 + 0.5) / steps as f64)
+                    .filled(),
+            )
+        }))
+        .unwrap();
+}
@@ -0,0 +1,359 @@
+use fundsp::fft::real_fft;
+use plotters::backend::SVGBackend;
+use plotters::coord::types::RangedCoordf64;
+use plotters::drawing::IntoDrawingArea;
+use plotters::element::DashedPathElement;
+use plotters::prelude::*;
+
+use crate::chart::{finish_svg, group_axis_color, layout_groups};
+use crate::chart_data::ChannelChartData;
+use crate::config::{SpectrumConfig, SvgChartConfig};
+use crate::data::SnapshotData;
+use crate::util::{frequency_formatter, get_contrasting_color, parse_hex_color};
+
+/// Largest FFT size supported by [fundsp::fft::real_fft]
+pub(crate) const MAX_FFT_SIZE: usize = 32768;
+/// Smallest magnitude plotted, avoids `-inf` dB
+const MIN_MAGNITUDE: f32 = 1e-12;
+const DEFAULT_WIDTH: usize = 1000;
+
+/// Window function applied to each FFT frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WindowFunction {
+    /// No windowing
+    Rectangular,
+    /// Hann window
+    #[default]
+    Hann,
+    /// Hamming window
+    Hamming,
+    /// Blackman window
+    Blackman,
+    /// 4-term Blackman-Harris window
+    BlackmanHarris,
+}
+
+impl WindowFunction {
+    /// Window coefficients for a frame of `size` samples
+    pub fn coefficients(&self, size: usize) -> Vec<f32> {
+        let n = size.max(2) as f64 - 1.0;
+        (0..size)
+            .map(|i| {
+                let x = 2.0 * std::f64::consts::PI * i as f64 / n;
+                let w = match self {
+                    WindowFunction::Rectangular => 1.0,
+                    WindowFunction::Hann => 0.5 - 0.5 * x.cos(),
+                    WindowFunction::Hamming => 0.54 - 0.46 * x.cos(),
+                    WindowFunction::Blackman => 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos(),
+                    WindowFunction::BlackmanHarris => {
+                        0.35875 - 0.48829 * x.cos() + 0.14128 * (2.0 * x).cos()
+                            - 0.01168 * (3.0 * x).cos()
+                    }
+                };
+                w as f32
+            })
+            .collect()
+    }
+}
+
+/// Resolve FFT size: requested size or the next power of two fitting `num_samples`,
+/// clamped to the range supported by [fundsp::fft::real_fft]
+pub(crate) fn fft_size(requested: Option<usize>, num_samples: usize) -> usize {
+    requested
+        .unwrap_or(num_samples)
+        .next_power_of_two()
+        .clamp(2, MAX_FFT_SIZE)
+}
+
+/// Amplitude spectrum of one frame, normalized so a full scale sine reads `1.0`
+///
+/// Frame is zero-padded to `fft_size`, returns `fft_size / 2 + 1` bins (DC..=Nyquist)
+pub(crate) fn frame_magnitudes(frame: &[f32], window: &[f32], fft_size: usize) -> Vec<f32> {
+    let mut buffer = vec![0.0; fft_size];
+    for (i, (sample, w)) in frame.iter().zip(window).enumerate() {
+        buffer[i] = sample * w;
+    }
+    let gain: f32 = window
+        .iter()
+        .take(frame.len())
+        .sum::<f32>()
+        .max(f32::EPSILON);
+
+    let bins = real_fft(&mut buffer);
+    let nyquist = bins[0].im;
+    let mut magnitudes: Vec<f32> = bins
+        .iter()
+        .enumerate()
+        .map(|(i, bin)| {
+            if i == 0 {
+                bin.re.abs() / gain
+            } else {
+                2.0 * bin.norm() / gain
+            }
+        })
+        .collect();
+    magnitudes.push(nyquist.abs() / gain);
+    magnitudes
+}
+
+/// Amplitude spectrum averaged over 50% overlapping frames (Welch)
+///
+/// Returns `fft_size / 2 + 1` bins (DC..=Nyquist)
+pub(crate) fn averaged_magnitudes(
+    samples: &[f32],
+    fft_size: usize,
+    window: WindowFunction,
+) -> Vec<f32> {
+    let frame_size = samples.len().min(fft_size);
+    let coefficients = window.coefficients(frame_size);
+    let hop = (frame_size / 2).max(1);
+
+    let mut power = vec![0.0f32; fft_size / 2 + 1];
+    let mut frames = 0;
+    let mut start = 0;
+    while start + frame_size <= samples.len() && frame_size > 0 {
+        let magnitudes =
+            frame_magnitudes(&samples[start..start + frame_size], &coefficients, fft_size);
+        for (p, m) in power.iter_mut().zip(magnitudes) {
+            *p += m * m;
+        }
+        frames += 1;
+        start += hop;
+    }
+
+    power
+        .into_iter()
+        .map(|p| (p / frames.max(1) as f32).sqrt())
+        .collect()
+}
+
+/// Convert amplitude to dB, clamped to `floor_db`
+pub(crate) fn amplitude_db(amplitude: f32, floor_db: f32) -> f32 {
+    (20.0 * amplitude.max(MIN_MAGNITUDE).log10()).max(floor_db)
+}
+
+/// Frequency of FFT bin
+pub(crate) fn bin_frequency(bin: usize, fft_size: usize, sample_rate: f64) -> f64 {
+    bin as f64 * sample_rate / fft_size as f64
+}
+
+pub(crate) fn generate_spectrum_svg(data: &SnapshotData, config: &SpectrumConfig) -> String {
+    let chart_config = &config.chart;
+    let num_inputs = if chart_config.with_inputs {
+        data.inputs.len()
+    } else {
+        0
+    };
+    let num_channels = data.outputs.len() + num_inputs;
+
+    if data.num_samples == 0 || num_channels == 0 {
+        return "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\"><text>Empty</text></svg>".to_string();
+    }
+
+    let fft_size = fft_size(config.fft_size, data.num_samples);
+    let nyquist = data.sample_rate / 2.0;
+    let max_frequency = config.max_frequency.unwrap_or(nyquist).min(nyquist);
+    let min_frequency = config
+        .min_frequency
+        .max(bin_frequency(1, fft_size, data.sample_rate))
+        .min(max_frequency / 2.0);
+
+    // Spectrum in dB per channel, limited to plotted frequency range
+    let frequencies: Vec<f64> = (1..=fft_size / 2)
+        .map(|bin| bin_frequency(bin, fft_size, data.sample_rate))
+        .collect();
+    let in_range = |f: &f64| *f >= min_frequency && *f <= max_frequency;
+    let spectrum_db = |samples: &[f32]| -> Vec<f32> {
+        averaged_magnitudes(samples, fft_size, config.window)
+            .into_iter()
+            .skip(1)
+            .zip(frequencies.iter())
+            .filter(|(_, f)| in_range(f))
+            .map(|(m, _)| amplitude_db(m, config.min_db))
+            .collect()
+    };
+    let plotted_frequencies: Vec<f64> = frequencies.iter().cloned().filter(in_range).collect();
+
+    let input_charts: Vec<ChannelChartData> = data
+        .inputs
+        .iter()
+        .take(num_inputs)
+        .enumerate()
+        .map(|(i, samples)| {
+            ChannelChartData::from_input_data(&spectrum_db(samples), i, chart_config)
+        })
+        .collect();
+    let output_charts: Vec<ChannelChartData> = data
+        .outputs
+        .iter()
+        .enumerate()
+        .map(|(i, samples)| {
+            ChannelChartData::from_output_data(&spectrum_db(samples), &[], i, chart_config)
+        })
+        .collect();
+
+    let groups = layout_groups(chart_config, input_charts, output_charts);
+
+    let svg_width = chart_config.svg_width.unwrap_or(DEFAULT_WIDTH) as u32;
+    let total_height = (chart_config.svg_height_per_channel * groups.len()) as u32;
+
+    let mut svg_buffer = String::new();
+    {
+        let root =
+            SVGBackend::with_string(&mut svg_buffer, (svg_width, total_height)).into_drawing_area();
+
+        let bg_color = parse_hex_color(&chart_config.background_color);
+        root.fill(&bg_color).unwrap();
+
+        let current_area = if let Some(ref title) = chart_config.chart_title {
+            let title_color = get_contrasting_color(&bg_color);
+            let text_style = TextStyle::from(("sans-serif", 20)).color(&title_color);
+            root.titled(title, text_style).unwrap()
+        } else {
+            root
+        };
+
+        let areas = current_area.split_evenly((groups.len(), 1));
+        for (group, area) in groups.iter().zip(areas) {
+            let max_db = group
+                .iter()
+                .flat_map(|c| c.data.iter())
+                .cloned()
+                .fold(config.min_db, f32::max);
+            let y_max = ((max_db / 10.0).ceil() * 10.0).max(config.min_db + 10.0) as f64;
+
+            log_frequency_chart(
+                group,
+                &plotted_frequencies,
+                chart_config,
+                group_axis_color(group),
+                &area,
+                config.min_db as f64..y_max,
+                "dB",
+            );
+        }
+
+        current_area.present().unwrap();
+    }
+
+    finish_svg(svg_buffer, chart_config, svg_width, total_height)
+}
+
+/// Plot channels against a logarithmic frequency axis
+///
+/// `frequencies` are shared X values for data of every channel
+pub(crate) fn log_frequency_chart<DB: DrawingBackend>(
+    charts_data: &[ChannelChartData],
+    frequencies: &[f64],
+    config: &SvgChartConfig,
+    axis_color: RGBColor,
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+    y_range: std::ops::Range<f64>,
+    y_desc: &str,
+) {
+    let min_frequency = frequencies.first().cloned().unwrap_or(1.0);
+    let max_frequency = frequencies
+        .last()
+        .cloned()
+        .unwrap_or(min_frequency * 2.0)
+        .max(min_frequency * 2.0);
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(5)
+        .x_label_area_size(if config.show_labels { 35 } else { 0 })
+        .y_label_area_size(if config.show_labels { 50 } else { 0 })
+        .build_cartesian_2d((min_frequency..max_frequency).log_scale(), y_range)
+        .unwrap();
+
+    let mut mesh = chart.configure_mesh();
+
+    mesh.axis_style(axis_color.mix(0.3));
+
+    if !config.show_grid {
+        mesh.disable_mesh();
+    } else {
+        mesh.light_line_style(axis_color.mix(0.1))
+            .bold_line_style(axis_color.mix(0.2));
+    }
+
+    let formatter = |v: &f64| frequency_formatter(*v);
+    let y_formatter = |v: &f64| format!("{v:.0}");
+    let x_desc = match charts_data {
+        [single] => single
+            .label
+            .as_ref()
+            .map(|label| format!("{label} (Hz)"))
+            .unwrap_or_else(|| "Hz".to_string()),
+        _ => "Hz".to_string(),
+    };
+    if config.show_labels {
+        mesh.x_labels(config.max_labels_x_axis.unwrap_or(10))
+            .y_labels(5)
+            .x_label_formatter(&formatter)
+            .y_label_formatter(&y_formatter)
+            .x_desc(x_desc)
+            .y_desc(y_desc)
+            .label_style(("sans-serif", 10, &axis_color));
+    }
+
+    mesh.draw().unwrap();
+
+    let mut has_legend = false;
+
+    for entry in charts_data {
+        let line_style = ShapeStyle {
+            color: entry.color.to_rgba(),
+            filled: false,
+            stroke_width: config.line_width as u32,
+        };
+
+        let points: Vec<(f64, f64)> = frequencies
+            .iter()
+            .zip(entry.data.iter())
+            .map(|(&f, &v)| (f, v as f64))
+            .collect();
+
+        let series = if entry.is_input && charts_data.iter().any(|c| !c.is_input) {
+            chart
+                .draw_series(std::iter::once(DashedPathElement::new(
+                    points, 2, 3, line_style,
+                )))
+                .unwrap()
+        } else {
+            chart
+                .draw_series(std::iter::once(PathElement::new(points, line_style)))
+                .unwrap()
+        };
+
+        if charts_data.len() > 1
+            && let Some(label) = &entry.label
+        {
+            let color = entry.color;
+            series
+                .label(label)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+            has_legend = true;
+        }
+    }
+
+    if has_legend {
+        draw_legend(&mut chart, config);
+    }
+}
+
+/// Draw series legend with colors contrasting chart background
+pub(crate) fn draw_legend<'a, DB: DrawingBackend + 'a, X: Ranged>(
+    chart: &mut ChartContext<'a, DB, Cartesian2d<X, RangedCoordf64>>,
+    config: &SvgChartConfig,
+) {
+    let background = parse_hex_color(&config.background_color);
+    let contrasting = get_contrasting_color(&background);
+
+    chart
+        .configure_series_labels()
+        .border_style(contrasting)
+        .background_style(background)
+        .label_font(TextStyle::from(("sans-serif", 10)).color(&contrasting))
+        .draw()
+        .unwrap();
+}
@@ -0,0 +1,295 @@
+use plotters::prelude::*;
+
+use crate::chart_data::ChannelChartData;
+use crate::config::SvgChartConfig;
+use crate::loudness::Loudness;
+use crate::util::{get_contrasting_color, parse_hex_color};
+
+/// Magnitude above which a sample counts as non-silent, -120 dBFS
+const SILENCE_THRESHOLD: f32 = 1e-6;
+
+/// Summary of one captured channel
+///
+/// ```
+/// use insta_fun::prelude::*;
+/// use fundsp::prelude::*;
+///
+/// let data = capture_audio_unit(
+///     pass() * 0.5,
+///     InputSource::sine(441.0, 44100.0),
+///     &SnapshotConfig::default(),
+/// );
+/// let statistics = &data.output_statistics()[0];
+/// assert!((statistics.peak_db() + 6.02).abs() < 0.01);
+/// assert_eq!(statistics.first_non_silent, Some(1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelStatistics {
+    /// Largest absolute sample value
+    pub peak: f32,
+    /// Root mean square of samples
+    pub rms: f32,
+    /// Mean of samples
+    pub dc_offset: f32,
+    /// Ratio of peak to RMS, `0.0` for silence
+    pub crest_factor: f32,
+    /// Number of sign changes, zero samples between them don't count
+    pub zero_crossings: usize,
+    /// Index of the first sample louder than -120 dBFS
+    pub first_non_silent: Option<usize>,
+    /// Index of the last sample louder than -120 dBFS
+    pub last_non_silent: Option<usize>,
+}
+
+impl ChannelStatistics {
+    /// Statistics of `samples`
+    pub fn from_samples(samples: &[f32]) -> Self {
+        let peak = samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+        let (sum, sum_of_squares) = samples
+            .iter()
+            .fold((0.0_f64, 0.0_f64), |(sum, squares), &s| {
+                (sum + s as f64, squares + s as f64 * s as f64)
+            });
+        let len = samples.len().max(1) as f64;
+        let rms = (sum_of_squares / len).sqrt() as f32;
+
+        let mut zero_crossings = 0;
+        let mut last_sign = 0.0;
+        for &sample in samples.iter().filter(|&&s| s != 0.0) {
+            let sign = sample.signum();
+            if last_sign != 0.0 && sign != last_sign {
+                zero_crossings += 1;
+            }
+            last_sign = sign;
+        }
+
+        let is_non_silent = |s: &f32| s.abs() > SILENCE_THRESHOLD;
+
+        Self {
+            peak,
+            rms,
+            dc_offset: (sum / len) as f32,
+            crest_factor: if rms > 0.0 { peak / rms } else { 0.0 },
+            zero_crossings,
+            first_non_silent: samples.iter().position(is_non_silent),
+            last_non_silent: samples.iter().rposition(is_non_silent),
+        }
+    }
+
+    /// Peak in dBFS
+    pub fn peak_db(&self) -> f32 {
+        20.0 * self.peak.log10()
+    }
+
+    /// RMS in dBFS
+    pub fn rms_db(&self) -> f32 {
+        20.0 * self.rms.log10()
+    }
+
+    /// Crest factor in dB
+    pub fn crest_factor_db(&self) -> f32 {
+        20.0 * self.crest_factor.log10()
+    }
+}
+
+/// Where waveform charts show channel statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatisticsDisplay {
+    /// Not shown
+    #[default]
+    Hidden,
+    /// Box in the bottom right corner of every chart, listing its channels
+    Legend,
+    /// Table under all charts, one row per plotted channel
+    Footer,
+}
+
+const FONT_SIZE: u32 = 10;
+const ROW_HEIGHT: u32 = 14;
+const FOOTER_COLUMNS: [&str; 7] = [
+    "Channel",
+    "Peak",
+    "RMS",
+    "DC",
+    "Crest",
+    "Zero crossings",
+    "Non-silent",
+];
+
+fn db_text(db: f32) -> String {
+    if db.is_finite() {
+        format!("{db:.2} dB")
+    } else {
+        "-inf dB".to_string()
+    }
+}
+
+fn crest_factor_text(statistics: &ChannelStatistics) -> String {
+    match statistics.crest_factor {
+        0.0 => "-".to_string(),
+        _ => db_text(statistics.crest_factor_db()),
+    }
+}
+
+fn non_silent_text(statistics: &ChannelStatistics, start_from: usize) -> String {
+    match (statistics.first_non_silent, statistics.last_non_silent) {
+        (Some(first), Some(last)) => format!("{}..={}", first + start_from, last + start_from),
+        _ => "silent".to_string(),
+    }
+}
+
+/// Cells of one footer row, as in [FOOTER_COLUMNS]
+fn footer_cells(
+    label: String,
+    statistics: &ChannelStatistics,
+    start_from: usize,
+) -> [String; FOOTER_COLUMNS.len()] {
+    [
+        label,
+        db_text(statistics.peak_db()),
+        db_text(statistics.rms_db()),
+        format!("{:.4}", statistics.dc_offset),
+        crest_factor_text(statistics),
+        statistics.zero_crossings.to_string(),
+        non_silent_text(statistics, start_from),
+    ]
+}
+
+fn channel_name(chart: &ChannelChartData) -> String {
+    chart.label.clone().unwrap_or_else(|| {
+        let kind = if chart.is_input { "Input" } else { "Output" };
+        format!("{kind} Ch#{}", chart.idx)
+    })
+}
+
+/// Height of the footer under all charts: statistics table of `num_channels` rows
+/// and loudness line, when shown
+pub(crate) fn footer_height(config: &SvgChartConfig, num_channels: usize) -> u32 {
+    let table_rows = match config.statistics {
+        StatisticsDisplay::Footer => num_channels as u32 + 1,
+        StatisticsDisplay::Hidden | StatisticsDisplay::Legend => 0,
+    };
+    match table_rows + config.show_loudness as u32 {
+        0 => 0,
+        rows => ROW_HEIGHT * rows + ROW_HEIGHT / 2,
+    }
+}
+
+/// Draw footer: table of `charts` statistics followed by `loudness` line
+pub(crate) fn draw_footer<DB: DrawingBackend>(
+    charts: &[&ChannelChartData],
+    loudness: Option<Loudness>,
+    config: &SvgChartConfig,
+    start_from: usize,
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+) {
+    let contrasting = get_contrasting_color(&parse_hex_color(&config.background_color));
+    let column_width = area.dim_in_pixel().0 as i32 / FOOTER_COLUMNS.len() as i32;
+    let cell = |text: String, column: usize, row: usize, color: RGBColor| {
+        area.draw(&Text::new(
+            text,
+            (
+                5 + column as i32 * column_width,
+                (ROW_HEIGHT as usize * row) as i32 + 2,
+            ),
+            TextStyle::from(("sans-serif", FONT_SIZE).into_font()).color(&color),
+        ))
+        .unwrap();
+    };
+
+    let mut rows = 0;
+    if config.statistics == StatisticsDisplay::Footer {
+        for (column, title) in FOOTER_COLUMNS.iter().enumerate() {
+            cell(title.to_string(), column, 0, contrasting);
+        }
+        for (row, chart) in charts.iter().enumerate() {
+            let statistics = ChannelStatistics::from_samples(&chart.data);
+            let cells = footer_cells(channel_name(chart), &statistics, start_from);
+            for (column, text) in cells.into_iter().enumerate() {
+                cell(text, column, row + 1, chart.color);
+            }
+        }
+        rows = charts.len() + 1;
+    }
+    if let Some(loudness) = loudness {
+        cell(
+            format!("Loudness: {}", loudness.summary()),
+            0,
+            rows,
+            contrasting,
+        );
+    }
+}
+
+/// Draw box of `charts` statistics in the bottom right corner of `plotting_area`
+pub(crate) fn draw_statistics_legend<DB: DrawingBackend>(
+    charts: &[ChannelChartData],
+    config: &SvgChartConfig,
+    start_from: usize,
+    plotting_area: &DrawingArea<DB, plotters::coord::Shift>,
+) {
+    if config.statistics != StatisticsDisplay::Legend || charts.is_empty() {
+        return;
+    }
+    let lines: Vec<(String, RGBColor)> = charts
+        .iter()
+        .map(|chart| {
+            let statistics = ChannelStatistics::from_samples(&chart.data);
+            let text = format!(
+                "{}: peak {}, RMS {}, DC {:.4}, crest {}, {} zero crossings, non-silent {}",
+                channel_name(chart),
+                db_text(statistics.peak_db()),
+                db_text(statistics.rms_db()),
+                statistics.dc_offset,
+                crest_factor_text(&statistics),
+                statistics.zero_crossings,
+                non_silent_text(&statistics, start_from),
+            );
+            (text, chart.color)
+        })
+        .collect();
+
+    let style = TextStyle::from(("sans-serif", FONT_SIZE).into_font());
+    let text_width = lines
+        .iter()
+        .filter_map(|(text, _)| plotting_area.estimate_text_size(text, &style).ok())
+        .map(|(width, _)| width as i32)
+        .max()
+        .unwrap_or_default();
+    let (width, height) = plotting_area.dim_in_pixel();
+    let box_size = (
+        text_width + 10,
+        (ROW_HEIGHT * lines.len() as u32) as i32 + 6,
+    );
+    let top_left = (
+        width as i32 - box_size.0 - 5,
+        height as i32 - box_size.1 - 5,
+    );
+
+    let background = parse_hex_color(&config.background_color);
+    let contrasting = get_contrasting_color(&background);
+    plotting_area
+        .draw(&Rectangle::new(
+            [top_left, (top_left.0 + box_size.0, top_left.1 + box_size.1)],
+            background.filled(),
+        ))
+        .unwrap();
+    plotting_area
+        .draw(&Rectangle::new(
+            [top_left, (top_left.0 + box_size.0, top_left.1 + box_size.1)],
+            contrasting.mix(0.5),
+        ))
+        .unwrap();
+    for (row, (text, color)) in lines.into_iter().enumerate() {
+        plotting_area
+            .draw(&Text::new(
+                text,
+                (
+                    top_left.0 + 5,
+                    top_left.1 + 4 + (ROW_HEIGHT as usize * row) as i32,
+                ),
+                style.color(&color),
+            ))
+            .unwrap();
+    }
+}
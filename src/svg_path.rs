@@ -0,0 +1,122 @@
+use crate::config::SvgChartConfig;
+
+const POINTS_ATTRIBUTE: &str = " points=\"";
+
+/// Quantise and simplify vertices of SVG polylines as configured
+///
+/// Only polylines with more than two vertices are touched,
+/// so waveforms and curves change while axes, ticks and grid lines stay
+pub(crate) fn simplify_paths(svg: String, config: &SvgChartConfig) -> String {
+    if config.path_precision.is_none() && config.path_simplification.is_none() {
+        return svg;
+    }
+
+    let mut simplified = String::with_capacity(svg.len());
+    let mut rest = svg.as_str();
+    while let Some(start) = rest.find("<polyline") {
+        simplified.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find('>').map(|end| end + 1).unwrap_or(rest.len());
+        simplified.push_str(&simplify_polyline(&rest[..end], config));
+        rest = &rest[end..];
+    }
+    simplified.push_str(rest);
+    simplified
+}
+
+fn simplify_polyline(element: &str, config: &SvgChartConfig) -> String {
+    let Some(start) = element
+        .find(POINTS_ATTRIBUTE)
+        .map(|start| start + POINTS_ATTRIBUTE.len())
+    else {
+        return element.to_string();
+    };
+    let Some(end) = element[start..].find('"').map(|end| start + end) else {
+        return element.to_string();
+    };
+
+    let points: Option<Vec<(f64, f64)>> = element[start..end]
+        .split_whitespace()
+        .map(|point| {
+            let (x, y) = point.split_once(',')?;
+            Some((x.parse().ok()?, y.parse().ok()?))
+        })
+        .collect();
+    let Some(points) = points.filter(|points| points.len() > 2) else {
+        return element.to_string();
+    };
+
+    let points = simplify(points, config.path_precision, config.path_simplification);
+    let points: String = points.iter().map(|(x, y)| format!("{x},{y} ")).collect();
+    format!("{}{points}{}", &element[..start], &element[end..])
+}
+
+/// Round vertices to multiples of `precision` pixels, drop repeated vertices,
+/// then simplify with Ramer–Douglas–Peucker within `tolerance` pixels
+fn simplify(
+    points: Vec<(f64, f64)>,
+    precision: Option<u32>,
+    tolerance: Option<f64>,
+) -> Vec<(f64, f64)> {
+    let step = precision.unwrap_or(1).max(1) as f64;
+    let mut quantised: Vec<(f64, f64)> = Vec::with_capacity(points.len());
+    for (x, y) in points {
+        let point = ((x / step).round() * step, (y / step).round() * step);
+        if quantised.last() != Some(&point) {
+            quantised.push(point);
+        }
+    }
+
+    match tolerance {
+        Some(tolerance) => ramer_douglas_peucker(&quantised, tolerance.max(0.0)),
+        None => quantised,
+    }
+}
+
+fn ramer_douglas_peucker(points: &[(f64, f64)], tolerance: f64) -> Vec<(f64, f64)> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut segments = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = segments.pop() {
+        let (farthest, distance) = (first + 1..last)
+            .map(|i| (i, segment_distance(points[i], points[first], points[last])))
+            .fold((first, 0.0), |farthest, candidate| {
+                if candidate.1 > farthest.1 {
+                    candidate
+                } else {
+                    farthest
+                }
+            });
+
+        if distance > tolerance {
+            keep[farthest] = true;
+            segments.push((first, farthest));
+            segments.push((farthest, last));
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(point, keep)| keep.then_some(*point))
+        .collect()
+}
+
+/// Distance from `point` to the segment between `a` and `b`
+fn segment_distance(point: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared > 0.0 {
+        (((point.0 - a.0) * dx + (point.1 - a.1) * dy) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (x, y) = (a.0 + t * dx, a.1 + t * dy);
+    ((point.0 - x).powi(2) + (point.1 - y).powi(2)).sqrt()
+}
@@ -1,4 +1,5 @@
 use fundsp::prelude::*;
+use std::path::Path;
 use std::{cell::RefCell, rc::Rc};
 
 use crate::assert_audio_unit_snapshot;
@@ -226,6 +227,23 @@ fn test_macro_variant_unit_and_config() {
     assert_audio_unit_snapshot!(unit, config);
 }
 
+#[test]
+fn test_macro_variant_multiple_output_modes() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(256)
+        .extra_output_mode(WavOutput::Wav16)
+        .build()
+        .unwrap();
+    // Input source driven by a unit is consumed by the single processing run
+    let input = InputSource::Unit(Box::new(sine_hz::<f32>(110.0)));
+    assert_audio_unit_snapshot!(
+        "macro_multiple_output_modes",
+        lowpass_hz(400.0, 0.7),
+        input,
+        config
+    );
+}
+
 #[test]
 fn test_chart_with_title() {
     let chart = SvgChartConfigBuilder::default()
@@ -595,6 +613,80 @@ fn test_abnormal_disallowed_panic() {
     );
 }
 
+#[test]
+fn test_try_abnormal_disallowed_error() {
+    let config = SnapshotConfigBuilder::default()
+        .allow_abnormal_samples(false)
+        .num_samples(10)
+        .build()
+        .unwrap();
+    let result = try_snapshot_audio_unit_with_input_and_options(
+        pass(),
+        InputSource::Generator(Box::new(
+            |sample, _| {
+                if sample == 3 { f32::NEG_INFINITY } else { 0.0 }
+            },
+        )),
+        config,
+    );
+
+    assert!(matches!(
+        result,
+        Err(SnapshotError::AbnormalSample {
+            channel: 0,
+            sample: 3,
+            kind: AbnormalSample::NegInf
+        })
+    ));
+}
+
+#[test]
+fn test_try_input_mismatch_errors() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(10)
+        .build()
+        .unwrap();
+    let result = try_snapshot_audio_unit_with_input_and_options(
+        pass(),
+        InputSource::VecByChannel(vec![vec![0.0; 10], vec![0.0; 10]]),
+        config.clone(),
+    );
+    assert!(matches!(
+        result,
+        Err(SnapshotError::InputChannelMismatch {
+            expected: 1,
+            actual: 2
+        })
+    ));
+
+    let result = try_snapshot_audio_unit_with_input_and_options(
+        pass(),
+        InputSource::VecByTick(vec![vec![0.0]; 5]),
+        config,
+    );
+    assert!(matches!(
+        result,
+        Err(SnapshotError::InputLengthMismatch {
+            expected: 10,
+            actual: 5
+        })
+    ));
+}
+
+#[test]
+fn test_try_invalid_batch_size_error() {
+    let config = SnapshotConfig {
+        processing_mode: Processing::Batch(65),
+        ..Default::default()
+    };
+    let result = try_snapshot_audio_unit_with_options(sine_hz::<f32>(440.0), config);
+
+    assert!(matches!(
+        result,
+        Err(SnapshotError::InvalidBatchSize { batch_size: 65 })
+    ));
+}
+
 #[test]
 fn test_chart_layout_combined() {
     let chart = SvgChartConfigBuilder::default()
@@ -681,130 +773,1779 @@ fn chart_x_axis_labels_as_time() {
     );
 }
 
-/* WAV output tests */
-
 #[test]
-fn test_wav16_basic() {
+fn test_decimation_long_snapshot() {
     let config = SnapshotConfigBuilder::default()
-        .output_mode(WavOutput::Wav16)
-        .num_samples(2048)
+        .num_samples(44100)
         .build()
         .unwrap();
-    let unit = sine_hz::<f32>(440.0);
-    let data = snapshot_audio_unit_with_input_and_options(unit, InputSource::None, config);
-    insta::assert_binary_snapshot!("wav16.wav", data);
+    let data = capture_audio_unit(sine_hz::<f32>(440.0), InputSource::None, &config);
+
+    let exact = data.render_svg(&SvgChartConfig::default());
+    let decimated = data.render_svg(
+        &SvgChartConfigBuilder::default()
+            .decimation(Decimation::MinMax)
+            .build()
+            .unwrap(),
+    );
+
+    assert!(decimated.starts_with(br#"<svg width="4000" "#));
+    assert!(exact.starts_with(br#"<svg width="88200" "#));
+    assert!(decimated.len() * 5 < exact.len());
 }
 
 #[test]
-fn test_wav32_basic() {
+fn test_decimation_min_max_rms() {
+    let chart = SvgChartConfigBuilder::default()
+        .svg_width(400)
+        .svg_height_per_channel(200)
+        .decimation(Decimation::MinMaxRms)
+        .build()
+        .unwrap();
     let config = SnapshotConfigBuilder::default()
-        .output_mode(WavOutput::Wav32)
-        .num_samples(2048)
+        .num_samples(8000)
+        .output_mode(chart)
         .build()
         .unwrap();
-    let unit = sine_hz::<f32>(440.0);
-    let data = snapshot_audio_unit_with_input_and_options(unit, InputSource::None, config);
-    insta::assert_binary_snapshot!("wav32.wav", data);
+    let unit = (noise() * envelope(|t: f32| exp(-t * 20.0))) >> lowpass_hz(2000.0, 0.7);
+
+    assert_audio_unit_snapshot!("decimation_min_max_rms", unit, InputSource::None, config);
 }
 
 #[test]
-fn test_wav16_with_warmup() {
+fn test_path_simplification() {
     let config = SnapshotConfigBuilder::default()
-        .warm_up(WarmUp::Samples(20_000))
-        .output_mode(WavOutput::Wav16)
-        .num_samples(2048)
+        .num_samples(1000)
         .build()
         .unwrap();
-    let unit = lowpass_hz(1000.0, 0.8);
-    let data = snapshot_audio_unit_with_input_and_options(unit, InputSource::impulse(), config);
-    insta::assert_binary_snapshot!("wav16_warmup.wav", data);
+    let data = capture_audio_unit(
+        lowpass_hz(500.0, 0.7),
+        InputSource::sine(100.0, DEFAULT_SR as f32),
+        &config,
+    );
+    let mut drifted = data.clone();
+    drifted.outputs[0]
+        .iter_mut()
+        .for_each(|sample| *sample += 1e-7);
+
+    let chart = SvgChartConfigBuilder::default()
+        .path_precision(2)
+        .path_simplification(0.5)
+        .build()
+        .unwrap();
+    let simplified = data.render_svg(&chart);
+
+    assert_eq!(simplified, drifted.render_svg(&chart));
+    assert!(simplified.len() < data.render_svg(&SvgChartConfig::default()).len());
 }
 
-#[cfg(feature = "dot")]
 #[test]
-fn test_dsp_net_snapshot() {
-    use fundsp::prelude::*;
+fn test_path_simplification_snapshot() {
+    let chart = SvgChartConfigBuilder::default()
+        .with_inputs(true)
+        .path_simplification(1.0)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(300)
+        .output_mode(chart)
+        .build()
+        .unwrap();
 
-    // Build a small but explicit stereo graph using Net APIs.
-    // Two global inputs feed two different chains; then merged to a stereo processor and piped to outputs.
-    let mut net = Net::new(2, 2);
+    assert_audio_unit_snapshot!(
+        "path_simplification",
+        lowpass_hz(1000.0, 0.7),
+        InputSource::impulse(),
+        config
+    );
+}
 
-    // Left chain: global in[0] -> lowpass
-    let left_filter = lowpass_hz(500.0, 0.7);
-    let left_id = net.push(Box::new(left_filter));
-    net.connect_input(0, left_id, 0);
+#[test]
+fn test_y_scale_decibel() {
+    let chart = SvgChartConfigBuilder::default()
+        .svg_width(400)
+        .svg_height_per_channel(200)
+        .y_scale(YScale::Decibel { floor: -120.0 })
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(4000)
+        .output_mode(chart)
+        .build()
+        .unwrap();
+    let unit = sine_hz::<f32>(440.0) * envelope(|t: f32| exp(-t * 100.0));
 
-    // Right chain: global in[1] -> highpass
-    let right_filter = highpass_hz(1200.0, 0.6);
-    let right_id = net.push(Box::new(right_filter));
-    net.connect_input(1, right_id, 0);
+    assert_audio_unit_snapshot!("y_scale_decibel", unit, InputSource::None, config);
+}
 
-    // Merge explicitly into a stereo pass-through to preserve channel mapping
-    let stereo = multipass::<U2>();
-    let stereo_id = net.push(Box::new(stereo));
-    // Wire left filter output -> stereo left input, right filter output -> stereo right input
-    net.connect(left_id, 0, stereo_id, 0);
-    net.connect(right_id, 0, stereo_id, 1);
+#[test]
+fn test_y_scale_symlog() {
+    let chart = SvgChartConfigBuilder::default()
+        .with_inputs(true)
+        .chart_layout(Layout::Combined)
+        .y_scale(YScale::SymLog {
+            linear_threshold: 0.001,
+        })
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(300)
+        .output_mode(chart)
+        .build()
+        .unwrap();
 
-    // Pipe to global outputs for a clear visualization
-    net.pipe_output(stereo_id);
+    assert_audio_unit_snapshot!(
+        "y_scale_symlog",
+        lowpass_hz(1000.0, 0.7),
+        InputSource::impulse(),
+        config
+    );
+}
 
-    assert_dsp_net_snapshot!("net_graph", net);
+#[test]
+fn test_y_scale_decibel_floor() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(100)
+        .build()
+        .unwrap();
+    let data = capture_audio_unit(zero(), InputSource::None, &config);
+    let chart = SvgChartConfigBuilder::default()
+        .y_scale(YScale::Decibel { floor: -60.0 })
+        .build()
+        .unwrap();
+
+    let svg = String::from_utf8(data.render_svg(&chart)).unwrap();
+    // Silence spans from the floor to full scale
+    assert!(svg.contains("-50 dB"));
+    assert!(svg.contains("\n0 dB\n"));
+    assert!(!svg.contains("inf"));
 }
 
-#[cfg(feature = "dot")]
 #[test]
-fn test_dsp_net_snapshot_complex() {
-    use fundsp::prelude::*;
+fn test_y_range_fixed_with_clipping() {
+    let chart = SvgChartConfigBuilder::default()
+        .svg_width(400)
+        .svg_height_per_channel(200)
+        .y_range(YRange::Fixed {
+            min: -1.5,
+            max: 1.5,
+        })
+        .show_clipping(true)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(200)
+        .output_mode(chart)
+        .build()
+        .unwrap();
+    let unit = sine_hz::<f32>(441.0) * envelope(|t: f32| 1.0 + t * 50.0);
 
-    // Complex explicit stereo graph using push/connect/pipe_all/connect_output APIs.
-    // Topology:
-    // - Generator A (sine 220) -> Lowpass -> Pan to stereo
-    // - Generator B (saw 330)  -> Highpass -> Pan to stereo
-    // - Mix via bus node (sum) -> Reverb stereo -> Global outputs
-    // - Additionally tap one intermediate node directly to OUT[1] for richer wiring
+    assert_audio_unit_snapshot!("y_range_fixed_clipping", unit, InputSource::None, config);
+}
 
-    let mut net = Net::new(0, 2);
+#[test]
+fn test_y_range_shared() {
+    let chart = SvgChartConfigBuilder::default()
+        .svg_width(400)
+        .svg_height_per_channel(200)
+        .y_range(YRange::Shared)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(200)
+        .output_mode(chart)
+        .build()
+        .unwrap();
+    let unit = sine_hz::<f32>(441.0) >> (pass() ^ mul(0.1));
 
-    // Generators
-    let gen_a = net.push(Box::new(sine_hz::<f32>(220.0))); // mono
-    let gen_b = net.push(Box::new(saw_hz(330.0))); // mono
+    assert_audio_unit_snapshot!("y_range_shared", unit, InputSource::None, config);
+}
 
-    // Filters
-    let lp = net.push(Box::new(lowpass_hz(400.0, 0.7))); // mono
-    let hp = net.push(Box::new(highpass_hz(800.0, 0.5))); // mono
+#[test]
+fn test_y_range_fixed_shows_gain() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(100)
+        .build()
+        .unwrap();
+    let loud = capture_audio_unit(sine_hz::<f32>(441.0), InputSource::None, &config);
+    let quiet = capture_audio_unit(sine_hz::<f32>(441.0) * 1e-6, InputSource::None, &config);
+    let fixed = SvgChartConfigBuilder::default()
+        .show_labels(false)
+        .y_range(YRange::Fixed {
+            min: -1.0,
+            max: 1.0,
+        })
+        .build()
+        .unwrap();
 
-    // Wire generators to filters explicitly
-    net.connect(gen_a, 0, lp, 0);
-    net.connect(gen_b, 0, hp, 0);
+    // Quiet signal is a flat line in the middle of the fixed range
+    let quiet_svg = String::from_utf8(quiet.render_svg(&fixed)).unwrap();
+    let points = quiet_svg.split(" points=\"").nth(1).unwrap();
+    let points = &points[..points.find('"').unwrap()];
+    assert!(
+        points
+            .split_whitespace()
+            .all(|point| point.ends_with(",250"))
+    );
+    assert_ne!(loud.render_svg(&fixed), quiet.render_svg(&fixed));
+}
 
-    // Pan each filtered mono signal to stereo
-    let pan_a = net.push(Box::new(pan(-0.25))); // slight left
-    let pan_b = net.push(Box::new(pan(0.25))); // slight right
-    net.pipe_all(lp, pan_a);
-    net.pipe_all(hp, pan_b);
+#[test]
+fn test_viewport_same_as_shorter_capture() {
+    let long = SnapshotConfigBuilder::default()
+        .num_samples(1000)
+        .build()
+        .unwrap();
+    let short = SnapshotConfigBuilder::default()
+        .num_samples(64)
+        .build()
+        .unwrap();
+    let chart = SvgChartConfigBuilder::default().build().unwrap();
+    let windowed = SvgChartConfigBuilder::default()
+        .viewport(0..64)
+        .build()
+        .unwrap();
 
-    // Bus/mix the two stereo streams into a stereo sum
-    let bus_mix = net.push(Box::new(multipass::<U2>() + multipass::<U2>())); // stereo + stereo -> stereo
-    // Connect left/right from pan_a into bus left/right
-    net.connect(pan_a, 0, bus_mix, 0);
-    net.connect(pan_a, 1, bus_mix, 1);
-    // Connect left/right from pan_b into bus left/right (sum second input)
-    // Using pipe_all to wrap-around would obscure port mapping; connect explicitly for clarity
-    net.connect(pan_b, 0, bus_mix, 0);
-    net.connect(pan_b, 1, bus_mix, 1);
+    let long = capture_audio_unit(lowpass_hz(1000.0, 0.7), InputSource::impulse(), &long);
+    let short = capture_audio_unit(lowpass_hz(1000.0, 0.7), InputSource::impulse(), &short);
+    assert_eq!(long.render_svg(&windowed), short.render_svg(&chart));
+}
 
-    // Stereo reverb after bus mix
-    let rev = net.push(Box::new(reverb_stereo(2.5, 0.6, 0.4)));
+#[test]
+fn test_viewport_keeps_start_sample() {
+    let chart = SvgChartConfigBuilder::default()
+        .with_inputs(true)
+        .viewport(100..164)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(1000)
+        .warm_up(WarmUp::Samples(1000))
+        .output_mode(chart)
+        .build()
+        .unwrap();
+    let unit = sine_hz::<f32>(441.0) >> lowpass_hz(500.0, 0.7);
 
-    // Pipe all channels from bus -> reverb
-    net.pipe_all(bus_mix, rev);
+    assert_audio_unit_snapshot!("viewport_warm_up", unit, InputSource::None, config);
+}
 
-    // Main path to both global outputs
-    net.pipe_output(rev);
+#[test]
+fn test_inset() {
+    let chart = SvgChartConfigBuilder::default()
+        .svg_width(800)
+        .svg_height_per_channel(300)
+        .inset(0..64)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(2000)
+        .output_mode(chart)
+        .build()
+        .unwrap();
+    let unit = sine_hz::<f32>(441.0) * envelope(|t: f32| (t * 500.0).min(1.0) * exp(-t * 20.0));
 
-    // Additional tap: connect the right output of pan_b directly to OUT[1] to visualize a parallel feed
-    net.connect_output(pan_b, 1, 1);
+    assert_audio_unit_snapshot!("inset", unit, InputSource::None, config);
+}
 
-    assert_dsp_net_snapshot!("complex_net_graph", net);
+#[test]
+fn test_inset_in_combined_viewport() {
+    let chart = SvgChartConfigBuilder::default()
+        .with_inputs(true)
+        .chart_layout(Layout::Combined)
+        .viewport(0..300)
+        .inset(10..40)
+        .format_x_axis_labels_as_time(true)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(1000)
+        .output_mode(chart)
+        .build()
+        .unwrap();
+
+    assert_audio_unit_snapshot!(
+        "inset_combined",
+        lowpass_hz(1000.0, 0.7),
+        InputSource::impulse(),
+        config
+    );
+}
+
+#[test]
+fn test_annotations() {
+    let chart = SvgChartConfigBuilder::default()
+        .svg_width(800)
+        .svg_height_per_channel(250)
+        .with_inputs(true)
+        .input_title("Gate")
+        .output_title("ADSR")
+        .annotation(Annotation::marker(0.005).label("gate on"))
+        .annotation(Annotation::marker(0.02).label("gate off").color("#EA4335"))
+        .annotation(
+            Annotation::region(0.02, 0.03)
+                .label("release")
+                .color("#FBBC04"),
+        )
+        .annotation(Annotation::level(0.5).label("sustain").color("#34A853"))
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(1764)
+        .output_mode(chart)
+        .build()
+        .unwrap();
+    let gate = InputSource::Generator(Box::new(
+        |i, _| {
+            if (220..882).contains(&i) { 1.0 } else { 0.0 }
+        },
+    ));
+
+    assert_audio_unit_snapshot!(
+        "annotations",
+        adsr_live(0.002, 0.005, 0.5, 0.005),
+        gate,
+        config
+    );
+}
+
+#[test]
+fn test_annotations_combined_layouts() {
+    for (name, layout) in [
+        ("annotations_combined", Layout::Combined),
+        (
+            "annotations_per_channel_type",
+            Layout::CombinedPerChannelType,
+        ),
+    ] {
+        let chart = SvgChartConfigBuilder::default()
+            .svg_width(400)
+            .svg_height_per_channel(200)
+            .chart_layout(layout)
+            .with_inputs(true)
+            .y_scale(YScale::Decibel { floor: -60.0 })
+            .annotation(Annotation::marker(32).label("impulse + 32"))
+            .annotation(Annotation::region(64, 96))
+            .annotation(Annotation::level_db(-3.0).label("-3 dB"))
+            .build()
+            .unwrap();
+        let config = SnapshotConfigBuilder::default()
+            .num_samples(200)
+            .output_mode(chart)
+            .build()
+            .unwrap();
+
+        assert_audio_unit_snapshot!(
+            name,
+            lowpass_hz(1000.0, 0.7),
+            InputSource::impulse(),
+            config
+        );
+    }
+}
+
+#[test]
+fn test_series_style_auto_stems() {
+    let chart = SvgChartConfigBuilder::default()
+        .svg_width(400)
+        .svg_height_per_channel(200)
+        .with_inputs(true)
+        .chart_layout(Layout::Combined)
+        .series_style(SeriesStyle::Auto)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(32)
+        .output_mode(chart)
+        .build()
+        .unwrap();
+
+    assert_audio_unit_snapshot!(
+        "series_auto_stems",
+        lowpass_hz(5000.0, 0.7),
+        InputSource::impulse(),
+        config
+    );
+}
+
+#[test]
+fn test_series_styles() {
+    for (name, style) in [
+        ("series_line", SeriesStyle::Line),
+        ("series_points", SeriesStyle::Points),
+        ("series_steps", SeriesStyle::Steps),
+    ] {
+        let chart = SvgChartConfigBuilder::default()
+            .svg_width(400)
+            .svg_height_per_channel(200)
+            .series_style(style)
+            .build()
+            .unwrap();
+        let config = SnapshotConfigBuilder::default()
+            .num_samples(32)
+            .output_mode(chart)
+            .build()
+            .unwrap();
+
+        assert_audio_unit_snapshot!(
+            name,
+            lowpass_hz(5000.0, 0.7),
+            InputSource::impulse(),
+            config
+        );
+    }
+}
+
+#[test]
+fn test_xy_transfer_curve() {
+    let chart = SvgChartConfigBuilder::default()
+        .chart_layout(Layout::XY {
+            x: ChartChannel::Input(0),
+            y: ChartChannel::Output(0),
+            mid_side: false,
+        })
+        .svg_height_per_channel(300)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(441)
+        .output_mode(chart)
+        .build()
+        .unwrap();
+
+    assert_audio_unit_snapshot!(
+        "xy_transfer_curve",
+        shape(Tanh(2.0)),
+        InputSource::sine(100.0, DEFAULT_SR as f32),
+        config
+    );
+}
+
+#[test]
+fn test_xy_mid_side() {
+    let chart = SvgChartConfigBuilder::default()
+        .chart_layout(Layout::XY {
+            x: ChartChannel::Output(0),
+            y: ChartChannel::Output(1),
+            mid_side: true,
+        })
+        .output_title("L")
+        .output_title("R")
+        .svg_height_per_channel(300)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(441)
+        .output_mode(chart)
+        .build()
+        .unwrap();
+    let unit = (sine_hz::<f32>(100.0) | dc(0.3)) >> panner();
+
+    assert_audio_unit_snapshot!("xy_mid_side", unit, InputSource::None, config);
+}
+
+#[test]
+fn test_xy_mono_is_vertical_in_mid_side() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(100)
+        .build()
+        .unwrap();
+    let data = capture_audio_unit(
+        sine_hz::<f32>(441.0) >> split::<U2>(),
+        InputSource::None,
+        &config,
+    );
+    let chart = SvgChartConfigBuilder::default()
+        .chart_layout(Layout::XY {
+            x: ChartChannel::Output(0),
+            y: ChartChannel::Output(1),
+            mid_side: true,
+        })
+        .show_labels(false)
+        .build()
+        .unwrap();
+
+    let svg = String::from_utf8(data.render_svg(&chart)).unwrap();
+    assert!(svg.starts_with(r#"<svg width="500" height="500""#));
+    // Every vertex of the trace sits on the mid axis
+    let trace = svg
+        .split("<polyline")
+        .find(|element| element.contains("stroke-width=\"2\""))
+        .unwrap();
+    let points = trace.split(" points=\"").nth(1).unwrap();
+    let points = &points[..points.find('"').unwrap()];
+    let first_x = points.split(',').next().unwrap();
+    assert!(
+        points
+            .split_whitespace()
+            .all(|point| point.split(',').next() == Some(first_x))
+    );
+}
+
+#[test]
+fn test_capture_renders_same_as_snapshot() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(300)
+        .warm_up(WarmUp::Samples(10))
+        .build()
+        .unwrap();
+
+    let data = capture_audio_unit(lowpass_hz(1000.0, 1.0), InputSource::impulse(), &config);
+
+    assert_eq!(data.inputs.len(), 1);
+    assert_eq!(data.outputs.len(), 1);
+    assert_eq!(data.outputs[0].len(), 300);
+    assert_eq!(data.inputs[0][0], 1.0);
+    assert_eq!(data.start_sample, 10);
+    assert_eq!(data.processing_mode, Processing::Tick);
+
+    let svg = snapshot_audio_unit_with_input_and_options(
+        lowpass_hz(1000.0, 1.0),
+        InputSource::impulse(),
+        config,
+    );
+    assert_eq!(data.render_svg(&SvgChartConfig::default()), svg);
+}
+
+#[test]
+fn test_raw_roundtrip() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(100)
+        .allow_abnormal_samples(true)
+        .processing_mode(Processing::Batch(32))
+        .build()
+        .unwrap();
+    let data = capture_audio_unit(
+        pass(),
+        InputSource::Generator(Box::new(|i, _| if i == 7 { f32::NAN } else { i as f32 })),
+        &config,
+    );
+
+    // Inputs contain NaN, compare encoded bytes instead of `PartialEq`
+    let decoded = SnapshotData::from_raw(&data.to_raw()).unwrap();
+    assert_eq!(decoded.to_raw(), data.to_raw());
+    assert_eq!(decoded.abnormalities, vec![vec![(7, AbnormalSample::Nan)]]);
+    assert_eq!(decoded.processing_mode, Processing::Batch(32));
+    assert!(matches!(
+        SnapshotData::from_raw(b"INSTAFUN"),
+        Err(SnapshotError::InvalidRawData(_))
+    ));
+}
+
+#[test]
+fn test_compare_with_tolerance() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(200)
+        .build()
+        .unwrap();
+    let expected = capture_audio_unit(lowpass_hz(1000.0, 0.7), InputSource::impulse(), &config);
+    let drifted = SnapshotData {
+        outputs: vec![
+            expected.outputs[0]
+                .iter()
+                .map(|s| s + 1e-6)
+                .collect::<Vec<_>>(),
+        ],
+        ..expected.clone()
+    };
+
+    assert!(
+        drifted
+            .compare(&expected, Tolerance::Absolute(1e-5))
+            .is_ok()
+    );
+    assert!(drifted.compare(&expected, Tolerance::Db(-96.0)).is_ok());
+    assert_eq!(
+        drifted.compare(&expected, Tolerance::Exact),
+        Err(SnapshotMismatch::Sample {
+            channel: 0,
+            sample: 0,
+            expected: expected.outputs[0][0],
+            actual: drifted.outputs[0][0],
+            tolerance: Tolerance::Exact,
+        })
+    );
+
+    let mut broken = drifted.clone();
+    broken.outputs[0][42] += 0.1;
+    assert!(matches!(
+        broken.compare(&expected, Tolerance::Absolute(1e-5)),
+        Err(SnapshotMismatch::Sample {
+            channel: 0,
+            sample: 42,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_tolerant_snapshot_bytes() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(64)
+        .build()
+        .unwrap();
+    let stored = capture_audio_unit(lowpass_hz(1000.0, 0.7), InputSource::impulse(), &config);
+    let path = std::env::temp_dir().join("insta_fun_tolerant_snapshot_bytes.raw");
+    std::fs::write(&path, stored.to_raw()).unwrap();
+
+    let drifted = capture_audio_unit(
+        lowpass_hz(1000.0, 0.7) * 1.000001,
+        InputSource::impulse(),
+        &config,
+    );
+    let (bytes, note) = crate::compare::__tolerant_snapshot_bytes(
+        &path,
+        drifted.to_raw(),
+        Tolerance::Absolute(1e-5),
+    );
+    assert_eq!(bytes, stored.to_raw());
+    assert_eq!(note, None);
+
+    let changed = capture_audio_unit(
+        lowpass_hz(1000.0, 0.7) * 0.5,
+        InputSource::impulse(),
+        &config,
+    );
+    let (bytes, note) = crate::compare::__tolerant_snapshot_bytes(
+        &path,
+        changed.to_raw(),
+        Tolerance::Absolute(1e-5),
+    );
+    assert_eq!(bytes, changed.to_raw());
+    assert!(
+        note.unwrap()
+            .contains("deviates beyond [absolute 1e-5] tolerance")
+    );
+
+    std::fs::remove_file(&path).unwrap();
+    let (bytes, note) = crate::compare::__tolerant_snapshot_bytes(
+        &path,
+        drifted.to_raw(),
+        Tolerance::Absolute(1e-5),
+    );
+    assert_eq!(bytes, drifted.to_raw());
+    assert!(note.unwrap().starts_with("No stored snapshot at"));
+}
+
+#[test]
+fn test_raw_snapshot_with_tolerance() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(256)
+        .output_mode(SnapshotOutputMode::Raw(Tolerance::Db(-90.0)))
+        .build()
+        .unwrap();
+    assert_audio_unit_snapshot!(
+        "raw_with_tolerance",
+        lowpass_hz(1000.0, 0.7),
+        InputSource::impulse(),
+        config
+    );
+}
+
+#[test]
+fn test_raw_unnamed_snapshots_with_tolerance() {
+    let config = || {
+        SnapshotConfigBuilder::default()
+            .num_samples(64)
+            .output_mode(SnapshotOutputMode::Raw(Tolerance::Db(-90.0)))
+            .build()
+            .unwrap()
+    };
+    assert_audio_unit_snapshot!(lowpass_hz(1000.0, 0.7), config());
+    assert_audio_unit_snapshot!(highpass_hz(1000.0, 0.7), config());
+
+    let stored = |name: &str| {
+        crate::compare::__stored_snapshot_path(
+            Path::new(env!("CARGO_MANIFEST_DIR")),
+            file!(),
+            Path::new("snapshots"),
+            Some(module_path!()),
+            name,
+            Some("raw"),
+        )
+    };
+    assert!(stored("raw_unnamed_snapshots_with_tolerance.raw").is_file());
+    assert!(stored("raw_unnamed_snapshots_with_tolerance-2.raw").is_file());
+}
+
+#[test]
+fn test_raw_snapshot_beyond_tolerance_reports_mismatch() {
+    let snapshot_path = std::env::temp_dir().join("insta_fun_raw_beyond_tolerance");
+    std::fs::create_dir_all(&snapshot_path).unwrap();
+    let config = || {
+        SnapshotConfigBuilder::default()
+            .num_samples(64)
+            .output_mode(SnapshotOutputMode::Raw(Tolerance::Db(-90.0)))
+            .build()
+            .unwrap()
+    };
+    let stored = capture_audio_unit(lowpass_hz(1000.0, 0.7), InputSource::impulse(), &config());
+    std::fs::write(
+        snapshot_path.join("insta_fun__tests__beyond_tolerance@raw.snap"),
+        "---\nsource: src/tests.rs\nextension: raw\nsnapshot_kind: binary\n---\n",
+    )
+    .unwrap();
+    std::fs::write(
+        snapshot_path.join("insta_fun__tests__beyond_tolerance@raw.snap.raw"),
+        stored.to_raw(),
+    )
+    .unwrap();
+
+    let result = std::panic::catch_unwind(|| {
+        insta::with_settings!({ snapshot_path => &snapshot_path }, {
+            assert_audio_unit_snapshot!(
+                "beyond_tolerance",
+                lowpass_hz(1000.0, 0.7) * 0.5,
+                InputSource::impulse(),
+                config()
+            );
+        });
+    });
+    let _ = std::fs::remove_dir_all(&snapshot_path);
+
+    let panic = result.unwrap_err();
+    let message = crate::compare::__panic_message(&*panic);
+    assert!(
+        message.contains("deviates beyond [-90 dBFS] tolerance"),
+        "{message}"
+    );
+}
+
+/* Spectrum tests */
+
+#[test]
+fn test_spectrum_sine_peak() {
+    let fft_size = 1024;
+    let bin = 32;
+    let frequency = crate::spectrum::bin_frequency(bin, fft_size, DEFAULT_SR);
+    let samples: Vec<f32> = (0..fft_size * 4)
+        .map(|i| (std::f64::consts::TAU * frequency * i as f64 / DEFAULT_SR).sin() as f32)
+        .collect();
+
+    for window in [
+        WindowFunction::Rectangular,
+        WindowFunction::Hann,
+        WindowFunction::Blackman,
+    ] {
+        let magnitudes = crate::spectrum::averaged_magnitudes(&samples, fft_size, window);
+        let (peak_bin, peak) = magnitudes
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+
+        assert_eq!(peak_bin, bin, "{window:?}");
+        assert!((peak - 1.0).abs() < 1e-3, "{window:?} peak {peak}");
+    }
+}
+
+#[test]
+fn test_spectrum_lowpass() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(4096)
+        .output_mode(
+            SpectrumConfigBuilder::default()
+                .chart(
+                    SvgChartConfigBuilder::default()
+                        .with_inputs(true)
+                        .show_grid(true)
+                        .chart_layout(Layout::Combined)
+                        .build()
+                        .unwrap(),
+                )
+                .window(WindowFunction::Rectangular)
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    assert_audio_unit_snapshot!(
+        "spectrum_lowpass",
+        lowpass_hz(1000.0, 0.7),
+        InputSource::impulse(),
+        config
+    );
+}
+
+#[test]
+fn test_spectrum_extra_output_mode() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(8192)
+        .extra_output_mode(
+            SpectrumConfigBuilder::default()
+                .fft_size(2048)
+                .min_db(-90.0)
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    assert_audio_unit_snapshot!("spectrum_saw", saw_hz(441.0), InputSource::None, config);
+}
+
+#[test]
+fn test_spectrogram_long_capture_is_binned_to_pixels() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(352800)
+        .build()
+        .unwrap();
+    let sweep = lfo(|t: f32| 100.0 + 10_000.0 * t) >> sine::<f32>();
+    let data = capture_audio_unit(sweep, InputSource::None, &config);
+    let svg = String::from_utf8(data.render_spectrogram(&SpectrogramConfig::default())).unwrap();
+
+    // One cell per frame and bin would be over 700000 rectangles
+    let num_rects = svg.matches("<rect").count();
+    assert!(num_rects < 100_000, "{num_rects} rectangles");
+}
+
+#[test]
+fn test_spectrogram_sweep_with_warmup() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(2048)
+        .warm_up(WarmUp::Samples(512))
+        .output_mode(
+            SpectrogramConfigBuilder::default()
+                .chart(
+                    SvgChartConfigBuilder::default()
+                        .format_x_axis_labels_as_time(true)
+                        .build()
+                        .unwrap(),
+                )
+                .fft_size(128)
+                .hop_size(64)
+                .min_frequency(300.0)
+                .min_db(-80.0)
+                .color_map(ColorMap::Magma)
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    let sweep = lfo(|t: f32| 1000.0 + 200_000.0 * t) >> sine::<f32>();
+    assert_audio_unit_snapshot!("spectrogram_sweep", sweep, InputSource::None, config);
+}
+
+#[test]
+fn test_color_map_range() {
+    for color_map in [
+        ColorMap::Viridis,
+        ColorMap::Magma,
+        ColorMap::Inferno,
+        ColorMap::Grayscale,
+    ] {
+        let stops = color_map.stops();
+        assert_eq!(color_map.color(0.0), crate::util::parse_hex_color(stops[0]));
+        assert_eq!(
+            color_map.color(1.0),
+            crate::util::parse_hex_color(stops[stops.len() - 1])
+        );
+    }
+    assert_eq!(
+        ColorMap::Grayscale.color(0.5),
+        plotters::style::RGBColor(128, 128, 128)
+    );
+}
+
+/* Frequency response tests */
+
+#[test]
+fn test_frequency_response_impulse_fallback() {
+    let config = FrequencyResponseConfigBuilder::default()
+        .max_frequency(10_000.0)
+        .num_points(32)
+        .build()
+        .unwrap();
+
+    let mut analytic_unit = lowpass_hz(1000.0, 0.7);
+    analytic_unit.set_sample_rate(DEFAULT_SR);
+    let analytic = measure_frequency_response(&mut analytic_unit, &config, DEFAULT_SR);
+
+    let mut measured_unit = lowpass_hz(1000.0, 0.7) >> map(|x: &Frame<f32, U1>| x[0]);
+    measured_unit.set_sample_rate(DEFAULT_SR);
+    let measured = measure_frequency_response(&mut measured_unit, &config, DEFAULT_SR);
+
+    assert_eq!(analytic.outputs[0].source, ResponseSource::Analytic);
+    assert_eq!(measured.outputs[0].source, ResponseSource::ImpulseResponse);
+    assert_eq!(analytic.frequencies, measured.frequencies);
+
+    let magnitudes = analytic.outputs[0]
+        .magnitude_db()
+        .into_iter()
+        .zip(measured.outputs[0].magnitude_db());
+    for (expected, actual) in magnitudes {
+        assert!(
+            (expected - actual).abs() < 0.1,
+            "{expected} dB vs {actual} dB"
+        );
+    }
+    let phases = analytic.outputs[0]
+        .phase_degrees()
+        .into_iter()
+        .zip(measured.outputs[0].phase_degrees());
+    for (expected, actual) in phases {
+        assert!((expected - actual).abs() < 1.0, "{expected}° vs {actual}°");
+    }
+}
+
+#[test]
+fn test_bode_lowpass_highpass() {
+    let config = SnapshotConfigBuilder::default()
+        .output_mode(
+            BodeConfigBuilder::default()
+                .chart(
+                    SvgChartConfigBuilder::default()
+                        .chart_layout(Layout::Combined)
+                        .show_grid(true)
+                        .output_title("Lowpass")
+                        .output_title("Highpass")
+                        .build()
+                        .unwrap(),
+                )
+                .min_db(-60.0)
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    assert_audio_unit_snapshot!(
+        "bode_lowpass_highpass",
+        lowpass_hz(1000.0, 0.7) | highpass_hz(1000.0, 0.7),
+        InputSource::impulse(),
+        config
+    );
+}
+
+#[test]
+fn test_group_delay_of_delay() {
+    let mut unit = delay(10.0 / DEFAULT_SR);
+    unit.set_sample_rate(DEFAULT_SR);
+    let response =
+        measure_frequency_response(&mut unit, &FrequencyResponseConfig::default(), DEFAULT_SR);
+    let output = &response.outputs[0];
+
+    assert_eq!(output.source, ResponseSource::Analytic);
+    for delay in output.group_delay_samples(&response.frequencies, response.sample_rate) {
+        assert!((delay - 10.0).abs() < 1e-6, "{delay} samples");
+    }
+
+    let phase = output.unwrapped_phase_degrees();
+    assert!(phase.windows(2).all(|w| w[1] < w[0]));
+    let expected = -360.0 * 10.0 * response.frequencies.last().unwrap() / DEFAULT_SR;
+    assert!((phase.last().unwrap() - expected).abs() < 1e-6);
+}
+
+#[test]
+fn test_group_delay_allpass() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(256)
+        .output_mode(GroupDelayConfig::default())
+        .build()
+        .unwrap();
+    assert_audio_unit_snapshot!(
+        "group_delay_allpass",
+        allpass_hz(1000.0, 0.707),
+        InputSource::impulse(),
+        config
+    );
+}
+
+#[test]
+fn test_try_render_bode_missing_response() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(64)
+        .build()
+        .unwrap();
+    let data = capture_audio_unit(lowpass_hz(1000.0, 0.7), InputSource::impulse(), &config);
+
+    assert!(data.frequency_responses.is_empty());
+    assert!(matches!(
+        data.try_render(&SnapshotOutputMode::Bode(BodeConfig::default())),
+        Err(SnapshotError::MissingFrequencyResponse)
+    ));
+}
+
+/* Text output tests */
+
+#[test]
+fn test_csv_with_inputs_and_warmup() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(16)
+        .warm_up(WarmUp::Samples(4))
+        .processing_mode(Processing::Batch(8))
+        .output_mode(SnapshotOutputMode::Csv(
+            SampleDumpConfigBuilder::default()
+                .precision(4)
+                .include_inputs(true)
+                .build()
+                .unwrap(),
+        ))
+        .build()
+        .unwrap();
+    assert_audio_unit_snapshot!(
+        "csv_lowpass",
+        lowpass_hz(5000.0, 0.7),
+        InputSource::impulse(),
+        config
+    );
+}
+
+#[test]
+fn test_json_abnormal_samples() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(8)
+        .allow_abnormal_samples(true)
+        .output_mode(SnapshotOutputMode::Json(SampleDumpConfig::default()))
+        .build()
+        .unwrap();
+    assert_audio_unit_snapshot!(
+        "json_abnormal",
+        pass(),
+        InputSource::Generator(Box::new(|sample, _| match sample {
+            2 => f32::NAN,
+            5 => f32::INFINITY,
+            _ => sample as f32 * 0.25,
+        })),
+        config
+    );
+}
+
+#[test]
+fn test_csv_auto_name_without_metadata() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(4)
+        .output_mode(SnapshotOutputMode::Csv(
+            SampleDumpConfigBuilder::default()
+                .precision(2)
+                .include_metadata(false)
+                .build()
+                .unwrap(),
+        ))
+        .build()
+        .unwrap();
+    assert_audio_unit_snapshot!(dc((0.5, -0.25)), config);
+}
+
+#[test]
+fn test_csv_and_json_with_short_channel() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(4)
+        .build()
+        .unwrap();
+    let data = SnapshotData {
+        num_samples: 6,
+        ..capture_audio_unit(dc(0.5), InputSource::None, &config)
+    };
+    let dump_config = SampleDumpConfigBuilder::default()
+        .precision(1)
+        .include_metadata(false)
+        .build()
+        .unwrap();
+
+    let csv = data.to_csv(&dump_config);
+    assert_eq!(
+        csv.lines().skip(1).collect::<Vec<_>>(),
+        ["0,0.5", "1,0.5", "2,0.5", "3,0.5", "4,", "5,"]
+    );
+    assert_eq!(data.to_json(&dump_config).matches("0.5").count(), 4);
+}
+
+/* Text chart tests */
+
+#[test]
+fn test_text_chart_inline() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(64)
+        .build()
+        .unwrap();
+    let data = capture_audio_unit(
+        pass(),
+        InputSource::sine(1000.0, DEFAULT_SR as f32),
+        &config,
+    );
+    let text_config = TextChartConfigBuilder::default()
+        .chart(
+            SvgChartConfigBuilder::default()
+                .with_inputs(true)
+                .input_titles(vec!["Sine".to_string()])
+                .output_titles(vec!["Pass".to_string()])
+                .build()
+                .unwrap(),
+        )
+        .width(32)
+        .height(3)
+        .build()
+        .unwrap();
+    insta::assert_snapshot!(data.render_text(&text_config), @"
+    Sine [-1.000, 1.000]
+    ⠀⢀⣠⠖⠚⠉⠉⠙⠲⢤⡀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⣠⠖⠚⠉⠉⠙⠲⢤
+    ⠴⠋⠀⠀⠀⠀⠀⠀⠀⠀⠙⠲⣄⠀⠀⠀⠀⠀⠀⠀⠀⣠⠴⠋⠀⠀⠀⠀⠀⠀⠀⠀
+    ⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠈⠙⠦⢤⣀⣀⣠⠴⠚⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
+    Pass [-1.000, 1.000]
+    ⠀⢀⣠⠖⠚⠉⠉⠙⠲⢤⡀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⣠⠖⠚⠉⠉⠙⠲⢤
+    ⠴⠋⠀⠀⠀⠀⠀⠀⠀⠀⠙⠲⣄⠀⠀⠀⠀⠀⠀⠀⠀⣠⠴⠋⠀⠀⠀⠀⠀⠀⠀⠀
+    ⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠈⠙⠦⢤⣀⣀⣠⠴⠚⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
+    ");
+}
+
+#[test]
+fn test_text_chart_blocks_decimated() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(4410)
+        .build()
+        .unwrap();
+    let data = capture_audio_unit(
+        pass(),
+        InputSource::Generator(Box::new(|i, _| 1.0 - (i as f32 / 2205.0).fract() * 2.0)),
+        &config,
+    );
+    let text_config = TextChartConfigBuilder::default()
+        .chart(
+            SvgChartConfigBuilder::default()
+                .chart_title("Saw")
+                .show_labels(false)
+                .build()
+                .unwrap(),
+        )
+        .width(20)
+        .height(3)
+        .style(TextChartStyle::Blocks)
+        .build()
+        .unwrap();
+    insta::assert_snapshot!(data.render_text(&text_config), @"
+    Saw
+    ▀█▄▄      ██▄▄
+       ▀▀█▄▄  █  ▀▀█▄▄
+           ▀▀██      ▀▀█
+    ");
+}
+
+#[test]
+fn test_text_chart_output_mode() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(256)
+        .output_mode(TextChartConfig::default())
+        .build()
+        .unwrap();
+    assert_audio_unit_snapshot!(
+        "lowpass_text",
+        lowpass_hz(1000.0, 0.7),
+        InputSource::impulse(),
+        config
+    );
+}
+
+/* PNG output tests */
+
+#[cfg(feature = "png")]
+#[test]
+fn test_png_output_mode() {
+    let chart = SvgChartConfigBuilder::default()
+        .with_inputs(true)
+        .chart_layout(Layout::Combined)
+        .svg_width(600)
+        .svg_height_per_channel(300)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(300)
+        .output_mode(SnapshotOutputMode::Png(chart))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        config.output_mode.file_name(Some("lowpass_png")),
+        "lowpass_png.png"
+    );
+    assert_audio_unit_snapshot!(
+        "lowpass_png",
+        lowpass_hz(1000.0, 0.7),
+        InputSource::impulse(),
+        config
+    );
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn test_png_is_deterministic() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(100)
+        .build()
+        .unwrap();
+    let data = capture_audio_unit(sine_hz::<f32>(440.0), InputSource::None, &config);
+    let chart = SvgChartConfigBuilder::default()
+        .chart_title("Sine")
+        .build()
+        .unwrap();
+
+    let png = data.render_png(&chart);
+    assert!(png.starts_with(b"\x89PNG"));
+    assert_eq!(png, data.render_png(&chart));
+}
+
+#[cfg(not(feature = "png"))]
+#[test]
+fn test_png_output_mode_without_feature() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(100)
+        .build()
+        .unwrap();
+    let data = capture_audio_unit(sine_hz::<f32>(440.0), InputSource::None, &config);
+    let output_mode = SnapshotOutputMode::Png(SvgChartConfig::default());
+
+    let err = data.try_render(&output_mode).unwrap_err();
+    assert!(matches!(err, SnapshotError::Png(_)));
+    assert!(err.to_string().contains("`png` feature"));
+}
+
+/* HTML output tests */
+
+#[test]
+fn test_html_output_mode() {
+    let chart = SvgChartConfigBuilder::default()
+        .with_inputs(true)
+        .chart_title("Lowpass <1 kHz>")
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(64)
+        .output_mode(SnapshotOutputMode::Html(chart))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        config.output_mode.file_name(Some("lowpass_html")),
+        "lowpass_html.html"
+    );
+    assert_audio_unit_snapshot!(
+        "lowpass_html",
+        lowpass_hz(1000.0, 0.7),
+        InputSource::impulse(),
+        config
+    );
+}
+
+#[test]
+fn test_html_is_self_contained() {
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(16)
+        .allow_abnormal_samples(true)
+        .build()
+        .unwrap();
+    let data = capture_audio_unit(
+        dc(1.0) >> map(|i: &Frame<f32, U1>| if i[0] > 0.0 { f32::NAN } else { 0.0 }),
+        InputSource::None,
+        &config,
+    );
+    let chart = SvgChartConfigBuilder::default()
+        .chart_title("</script> & more")
+        .build()
+        .unwrap();
+
+    let html = String::from_utf8(data.render_html(&chart)).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<h1>&lt;/script&gt; &amp; more</h1>"));
+    assert!(html.contains("<script type=\"application/json\" id=\"snapshot-data\">"));
+    assert!(html.contains("\"abnormalities\":[[0,\"NaN\"]"));
+    assert!(html.contains("\"samples\":[0.0,"));
+    assert!(!html.contains(" src="));
+    assert!(!html.contains(" href="));
+    assert_eq!(html.matches("</script>").count(), 2);
+}
+
+/* WAV output tests */
+
+#[test]
+fn test_wav16_basic() {
+    let config = SnapshotConfigBuilder::default()
+        .output_mode(WavOutput::Wav16)
+        .num_samples(2048)
+        .build()
+        .unwrap();
+    let unit = sine_hz::<f32>(440.0);
+    let data = snapshot_audio_unit_with_input_and_options(unit, InputSource::None, config);
+    insta::assert_binary_snapshot!("wav16.wav", data);
+}
+
+#[test]
+fn test_wav32_basic() {
+    let config = SnapshotConfigBuilder::default()
+        .output_mode(WavOutput::Wav32)
+        .num_samples(2048)
+        .build()
+        .unwrap();
+    let unit = sine_hz::<f32>(440.0);
+    let data = snapshot_audio_unit_with_input_and_options(unit, InputSource::None, config);
+    insta::assert_binary_snapshot!("wav32.wav", data);
+}
+
+#[test]
+fn test_wav16_with_warmup() {
+    let config = SnapshotConfigBuilder::default()
+        .warm_up(WarmUp::Samples(20_000))
+        .output_mode(WavOutput::Wav16)
+        .num_samples(2048)
+        .build()
+        .unwrap();
+    let unit = lowpass_hz(1000.0, 0.8);
+    let data = snapshot_audio_unit_with_input_and_options(unit, InputSource::impulse(), config);
+    insta::assert_binary_snapshot!("wav16_warmup.wav", data);
+}
+
+#[cfg(feature = "dot")]
+#[test]
+fn test_dsp_net_snapshot() {
+    use fundsp::prelude::*;
+
+    // Build a small but explicit stereo graph using Net APIs.
+    // Two global inputs feed two different chains; then merged to a stereo processor and piped to outputs.
+    let mut net = Net::new(2, 2);
+
+    // Left chain: global in[0] -> lowpass
+    let left_filter = lowpass_hz(500.0, 0.7);
+    let left_id = net.push(Box::new(left_filter));
+    net.connect_input(0, left_id, 0);
+
+    // Right chain: global in[1] -> highpass
+    let right_filter = highpass_hz(1200.0, 0.6);
+    let right_id = net.push(Box::new(right_filter));
+    net.connect_input(1, right_id, 0);
+
+    // Merge explicitly into a stereo pass-through to preserve channel mapping
+    let stereo = multipass::<U2>();
+    let stereo_id = net.push(Box::new(stereo));
+    // Wire left filter output -> stereo left input, right filter output -> stereo right input
+    net.connect(left_id, 0, stereo_id, 0);
+    net.connect(right_id, 0, stereo_id, 1);
+
+    // Pipe to global outputs for a clear visualization
+    net.pipe_output(stereo_id);
+
+    assert_dsp_net_snapshot!("net_graph", net);
+}
+
+#[cfg(feature = "dot")]
+#[test]
+fn test_dsp_net_snapshot_complex() {
+    use fundsp::prelude::*;
+
+    // Complex explicit stereo graph using push/connect/pipe_all/connect_output APIs.
+    // Topology:
+    // - Generator A (sine 220) -> Lowpass -> Pan to stereo
+    // - Generator B (saw 330)  -> Highpass -> Pan to stereo
+    // - Mix via bus node (sum) -> Reverb stereo -> Global outputs
+    // - Additionally tap one intermediate node directly to OUT[1] for richer wiring
+
+    let mut net = Net::new(0, 2);
+
+    // Generators
+    let gen_a = net.push(Box::new(sine_hz::<f32>(220.0))); // mono
+    let gen_b = net.push(Box::new(saw_hz(330.0))); // mono
+
+    // Filters
+    let lp = net.push(Box::new(lowpass_hz(400.0, 0.7))); // mono
+    let hp = net.push(Box::new(highpass_hz(800.0, 0.5))); // mono
+
+    // Wire generators to filters explicitly
+    net.connect(gen_a, 0, lp, 0);
+    net.connect(gen_b, 0, hp, 0);
+
+    // Pan each filtered mono signal to stereo
+    let pan_a = net.push(Box::new(pan(-0.25))); // slight left
+    let pan_b = net.push(Box::new(pan(0.25))); // slight right
+    net.pipe_all(lp, pan_a);
+    net.pipe_all(hp, pan_b);
+
+    // Bus/mix the two stereo streams into a stereo sum
+    let bus_mix = net.push(Box::new(multipass::<U2>() + multipass::<U2>())); // stereo + stereo -> stereo
+    // Connect left/right from pan_a into bus left/right
+    net.connect(pan_a, 0, bus_mix, 0);
+    net.connect(pan_a, 1, bus_mix, 1);
+    // Connect left/right from pan_b into bus left/right (sum second input)
+    // Using pipe_all to wrap-around would obscure port mapping; connect explicitly for clarity
+    net.connect(pan_b, 0, bus_mix, 0);
+    net.connect(pan_b, 1, bus_mix, 1);
+
+    // Stereo reverb after bus mix
+    let rev = net.push(Box::new(reverb_stereo(2.5, 0.6, 0.4)));
+
+    // Pipe all channels from bus -> reverb
+    net.pipe_all(bus_mix, rev);
+
+    // Main path to both global outputs
+    net.pipe_output(rev);
+
+    // Additional tap: connect the right output of pan_b directly to OUT[1] to visualize a parallel feed
+    net.connect_output(pan_b, 1, 1);
+
+    assert_dsp_net_snapshot!("complex_net_graph", net);
+}
+
+#[test]
+fn test_input_ramp() {
+    let data = InputSource::ramp(-2.0, 2.0, 5).make_data(2, 5);
+    assert_eq!(data[0], vec![-2.0, -1.0, 0.0, 1.0, 2.0]);
+    assert_eq!(data[1], data[0]);
+}
+
+#[test]
+fn test_transfer_curve_tanh() {
+    let config = TransferCurveConfigBuilder::default()
+        .show_derivative(true)
+        .build()
+        .unwrap();
+    assert_transfer_curve_snapshot!("transfer_curve_tanh", shape(Tanh(2.0)), config);
+}
+
+#[test]
+fn test_transfer_curve_combined() {
+    let chart = SvgChartConfigBuilder::default()
+        .chart_layout(Layout::Combined)
+        .output_titles(vec![
+            "clip".to_string(),
+            "tanh".to_string(),
+            "softsign".to_string(),
+        ])
+        .build()
+        .unwrap();
+    let config = TransferCurveConfigBuilder::default()
+        .chart(chart)
+        .num_points(256)
+        .build()
+        .unwrap();
+    let unit = clip() ^ shape(Tanh(1.0)) ^ shape(Softsign(1.0));
+    assert_transfer_curve_snapshot!("transfer_curve_combined", unit, config);
+}
+
+#[test]
+fn test_channel_statistics() {
+    let statistics = ChannelStatistics::from_samples(&[0.0, 0.0, 0.5, -0.5, 0.0, 0.5, 0.0]);
+    assert_eq!(statistics.peak, 0.5);
+    assert_eq!(statistics.zero_crossings, 2);
+    assert_eq!(statistics.first_non_silent, Some(2));
+    assert_eq!(statistics.last_non_silent, Some(5));
+    assert!((statistics.dc_offset - 0.5 / 7.0).abs() < 1e-6);
+    assert!((statistics.crest_factor - 0.5 / statistics.rms).abs() < 1e-6);
+
+    let silence = ChannelStatistics::from_samples(&[0.0; 16]);
+    assert_eq!(silence.peak, 0.0);
+    assert_eq!(silence.crest_factor, 0.0);
+    assert_eq!(silence.first_non_silent, None);
+
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(1000)
+        .build()
+        .unwrap();
+    let data = capture_audio_unit(
+        pass() * db_amp(-0.5),
+        InputSource::sine(441.0, 44100.0),
+        &config,
+    );
+    let [input] = data.input_statistics()[..] else {
+        panic!("one input channel");
+    };
+    let [output] = data.output_statistics()[..] else {
+        panic!("one output channel");
+    };
+    assert!((output.peak_db() - input.peak_db() + 0.5).abs() < 1e-3);
+    assert!((input.crest_factor_db() - 3.01).abs() < 0.01);
+    assert_eq!(input.zero_crossings, 19);
+}
+
+#[test]
+fn test_statistics_footer() {
+    let chart = SvgChartConfigBuilder::default()
+        .with_inputs(true)
+        .statistics(StatisticsDisplay::Footer)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(512)
+        .output_mode(chart)
+        .build()
+        .unwrap();
+    assert_audio_unit_snapshot!(
+        "statistics_footer",
+        lowpass_hz(1000.0, 1.0),
+        InputSource::impulse(),
+        config
+    );
+}
+
+#[test]
+fn test_statistics_legend() {
+    let chart = SvgChartConfigBuilder::default()
+        .chart_layout(Layout::Combined)
+        .statistics(StatisticsDisplay::Legend)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(512)
+        .output_mode(chart)
+        .build()
+        .unwrap();
+    let unit = (sine_hz::<f32>(220.0) * 0.5) | (saw_hz(110.0) * 0.25 + 0.1);
+    assert_audio_unit_snapshot!("statistics_legend", unit, InputSource::None, config);
+}
+
+#[test]
+fn test_loudness_reference_sine() {
+    let sample_rate = 48000.0;
+    let sine: Vec<f32> = (0..48000 * 3)
+        .map(|i| (std::f64::consts::TAU * 997.0 * i as f64 / sample_rate).sin() as f32)
+        .collect();
+
+    let mono = Loudness::measure(std::slice::from_ref(&sine), sample_rate);
+    assert!((mono.integrated + 3.01).abs() < 0.05, "{mono:?}");
+    assert!((mono.max_short_term + 3.01).abs() < 0.05, "{mono:?}");
+    assert!((mono.max_momentary + 3.01).abs() < 0.05, "{mono:?}");
+    assert!(mono.true_peak.abs() < 0.05, "{mono:?}");
+
+    let stereo = Loudness::measure(&[sine.clone(), sine], sample_rate);
+    assert!((stereo.integrated - mono.integrated - 3.01).abs() < 0.01);
+}
+
+#[test]
+fn test_loudness_gates_silence_and_short_captures() {
+    let sample_rate = 48000.0;
+    let tone_then_silence: Vec<f32> = (0..48000 * 4)
+        .map(|i| {
+            if i < 48000 {
+                0.5 * (std::f64::consts::TAU * 997.0 * i as f64 / sample_rate).sin() as f32
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let loudness = Loudness::measure(&[tone_then_silence], sample_rate);
+    // 7 blocks within the tone and 3 blocks partly overlapping it pass the gates,
+    // silent blocks don't count
+    let expected = -9.03 + 10.0 * (8.5_f64 / 10.0).log10();
+    assert!(
+        (loudness.integrated - expected).abs() < 0.05,
+        "{loudness:?}"
+    );
+    assert!((loudness.max_momentary + 9.03).abs() < 0.05, "{loudness:?}");
+
+    let short = Loudness::measure(&[vec![0.5; 1024]], sample_rate);
+    assert_eq!(short.integrated, f64::NEG_INFINITY);
+    assert_eq!(short.max_short_term, f64::NEG_INFINITY);
+    // Interpolation overshoots the step from silence, never undershoots the sample peak
+    assert!(short.true_peak >= -6.03);
+}
+
+#[test]
+fn test_true_peak_between_samples() {
+    // Quarter sample rate sine sampled 45° off its peaks
+    let samples: Vec<f32> = (0..4096)
+        .map(|i| (std::f32::consts::FRAC_PI_2 * i as f32 + std::f32::consts::FRAC_PI_4).sin())
+        .collect();
+    let sample_peak = samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+    assert!((20.0 * sample_peak.log10() + 3.01).abs() < 0.01);
+
+    let loudness = Loudness::measure(&[samples], 48000.0);
+    assert!(loudness.true_peak.abs() < 0.1, "{loudness:?}");
+}
+
+#[test]
+fn test_loudness_limits() {
+    let limits = LoudnessLimitsBuilder::default()
+        .max_true_peak(-1.0)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .num_samples(22050)
+        .loudness_limits(limits.clone())
+        .build()
+        .unwrap();
+
+    let result = try_snapshot_audio_unit_with_input_and_options(
+        pass(),
+        InputSource::sine(1000.0, 44100.0),
+        config.clone(),
+    );
+    assert!(matches!(
+        result,
+        Err(SnapshotError::LoudnessLimitExceeded {
+            metric: "true peak",
+            ..
+        })
+    ));
+
+    let result = try_snapshot_audio_unit_with_input_and_options(
+        pass() * db_amp(-3.0),
+        InputSource::sine(1000.0, 44100.0),
+        config,
+    );
+    assert!(result.is_ok());
+
+    let data = capture_audio_unit(
+        pass() * 0.5,
+        InputSource::sine(1000.0, 44100.0),
+        &SnapshotConfigBuilder::default()
+            .num_samples(44100)
+            .build()
+            .unwrap(),
+    );
+    let target = LoudnessLimitsBuilder::default()
+        .target_integrated(-14.0)
+        .build()
+        .unwrap();
+    assert!(matches!(
+        data.loudness().check(&target),
+        Err(SnapshotError::LoudnessLimitExceeded { limit: -13.0, .. })
+    ));
+}
+
+#[test]
+fn test_loudness_in_chart_and_metadata() {
+    let chart = SvgChartConfigBuilder::default()
+        .show_loudness(true)
+        .build()
+        .unwrap();
+    let config = SnapshotConfigBuilder::default()
+        .sample_rate(8000.0)
+        .num_samples(8000)
+        .output_mode(chart)
+        .extra_output_mode(WavOutput::Wav16)
+        .loudness_metadata(true)
+        .build()
+        .unwrap();
+    let unit = (sine_hz::<f32>(440.0) * 0.5) >> split::<U2>();
+    assert_audio_unit_snapshot!("loudness_stereo", unit, InputSource::None, config);
+}
+
+#[test]
+fn test_harmonics_of_pure_sine() {
+    let config = HarmonicsConfigBuilder::default()
+        .frequency(1234.5)
+        .build()
+        .unwrap();
+    let analysis = &analyze_harmonics(pass() * 0.5, &config)[0];
+
+    assert!((analysis.fundamental.frequency - 1234.5).abs() < 0.5);
+    assert!((analysis.fundamental.level + 6.02).abs() < 0.01);
+    assert_eq!(analysis.harmonics.len(), 9);
+    assert!(analysis.thd_db() < -110.0);
+    assert!(analysis.thd_n_db() < -80.0);
+}
+
+#[test]
+fn test_harmonics_of_saturator_and_oscillator() {
+    let config = HarmonicsConfig::default();
+    let analysis = &analyze_harmonics(shape(Tanh(4.0)), &config)[0];
+    for harmonic in &analysis.harmonics {
+        if harmonic.number % 2 == 0 {
+            assert!(harmonic.relative_level < -100.0, "{harmonic:?}");
+        } else {
+            assert!(harmonic.relative_level > -40.0, "{harmonic:?}");
+        }
+    }
+    assert!((analysis.thd_n - analysis.thd).abs() < 1e-3);
+
+    // Free-running band-limited saw, harmonics fall off as 1/n
+    let config = HarmonicsConfigBuilder::default()
+        .frequency(440.0)
+        .num_harmonics(20)
+        .build()
+        .unwrap();
+    let analysis = &analyze_harmonics(saw_hz(440.0), &config)[0];
+    assert_eq!(analysis.harmonic(20).unwrap().number, 20);
+    for harmonic in &analysis.harmonics {
+        let expected = -20.0 * (harmonic.number as f64).log10();
+        assert!(
+            (harmonic.relative_level - expected).abs() < 0.5,
+            "{harmonic:?}"
+        );
+    }
+}
+
+#[test]
+fn test_harmonics_snapshot() {
+    let chart = SvgChartConfigBuilder::default()
+        .chart_layout(Layout::Combined)
+        .output_titles(vec!["tanh".to_string(), "softsign".to_string()])
+        .build()
+        .unwrap();
+    let config = HarmonicsConfigBuilder::default()
+        .chart(chart)
+        .frequency(441.0)
+        .num_samples(4096)
+        .build()
+        .unwrap();
+    let unit = shape(Tanh(2.0)) ^ shape(Softsign(2.0));
+    assert_harmonics_snapshot!("harmonics_saturators", unit, config);
+}
+
+#[test]
+fn test_aliasing_of_oscillators_and_clipper() {
+    let config = AliasingConfigBuilder::default()
+        .num_frequencies(6)
+        .build()
+        .unwrap();
+    let sweep = measure_aliasing(saw_hz, &config);
+    assert_eq!(sweep.outputs.len(), 1);
+    assert_eq!(sweep.outputs[0].len(), 6);
+    assert_eq!(sweep.outputs[0][0].frequency, 100.0);
+    assert!((sweep.outputs[0][5].frequency - 10000.0).abs() < 1e-6);
+    assert!(sweep.worst().unwrap().alias_level < -75.0);
+    assert!(sweep.check(-75.0).is_ok());
+
+    // Hard clipping a sine without oversampling folds harmonics back below Nyquist
+    let sweep = measure_aliasing(|_| mul(2.0) >> clip(), &config);
+    let worst = sweep.worst().unwrap();
+    assert_eq!(worst.frequency, sweep.outputs[0][5].frequency);
+    assert!(worst.alias_level > -30.0);
+    assert!(worst.max_alias_level <= worst.alias_level);
+    assert!(matches!(
+        sweep.check(-60.0),
+        Err(SnapshotError::AliasingLimitExceeded { channel: 0, .. })
+    ));
+}
+
+#[test]
+fn test_aliasing_sweep_range_is_clamped() {
+    let sample_rate = fundsp::DEFAULT_SR;
+    let min_resolvable =
+        min_resolvable_frequency(WindowFunction::BlackmanHarris, 16384, sample_rate);
+
+    // Fundamentals too low to separate harmonic lobes are raised to the resolvable one
+    for min_frequency in [0.0, 5.0] {
+        let config = AliasingConfigBuilder::default()
+            .min_frequency(min_frequency)
+            .max_frequency(1000.0)
+            .num_frequencies(3)
+            .build()
+            .unwrap();
+        let sweep = measure_aliasing(|_| mul(2.0) >> clip(), &config);
+        let lowest = &sweep.outputs[0][0];
+        assert_eq!(lowest.frequency, min_resolvable);
+        assert!(lowest.alias_level.is_finite() && lowest.alias_level > -200.0);
+        assert!(lowest.max_alias_level.is_finite());
+    }
+
+    // Fundamentals at or above Nyquist are lowered below it
+    let config = AliasingConfigBuilder::default()
+        .min_frequency(1000.0)
+        .max_frequency(sample_rate)
+        .num_frequencies(3)
+        .build()
+        .unwrap();
+    let sweep = measure_aliasing(sine_hz::<f32>, &config);
+    let highest = &sweep.outputs[0][2];
+    assert!(highest.frequency < sample_rate / 2.0);
+    assert!(highest.fundamental_level > -1.0);
+    assert!(sweep.worst().unwrap().alias_level < -60.0);
+
+    let sweep = measure_aliasing(|_| mul(2.0) >> clip(), &config);
+    assert!(sweep.outputs[0][2].fundamental_level > -1.0);
+}
+
+#[test]
+fn test_aliasing_snapshot() {
+    let config = AliasingConfigBuilder::default()
+        .num_frequencies(8)
+        .num_samples(4096)
+        .max_alias_level(-60.0)
+        .build()
+        .unwrap();
+    assert_aliasing_snapshot!("aliasing_square", square_hz, config);
 }
@@ -0,0 +1,174 @@
+use std::fmt::Write;
+
+use crate::abnormal::AbnormalSample;
+use crate::config::{Processing, SampleDumpConfig};
+use crate::data::SnapshotData;
+
+/// Channel included in dump
+struct DumpChannel<'a> {
+    name: String,
+    samples: &'a [f32],
+    abnormalities: &'a [(usize, AbnormalSample)],
+}
+
+fn dump_channels<'a>(data: &'a SnapshotData, config: &SampleDumpConfig) -> Vec<DumpChannel<'a>> {
+    let inputs = data
+        .inputs
+        .iter()
+        .enumerate()
+        .filter(|_| config.include_inputs)
+        .map(|(i, samples)| DumpChannel {
+            name: format!("input_{i}"),
+            samples,
+            abnormalities: &[],
+        });
+    let outputs = data.outputs.iter().enumerate().map(|(i, samples)| {
+        let abnormalities = data
+            .abnormalities
+            .get(i)
+            .map(|a| a.as_slice())
+            .unwrap_or_default();
+        DumpChannel {
+            name: format!("output_{i}"),
+            samples,
+            abnormalities,
+        }
+    });
+    inputs.chain(outputs).collect()
+}
+
+fn processing_mode(mode: Processing) -> String {
+    match mode {
+        Processing::Tick => "tick".to_string(),
+        Processing::Batch(batch_size) => format!("batch({batch_size})"),
+    }
+}
+
+fn abnormality_at(abnormalities: &[(usize, AbnormalSample)], i: usize) -> Option<AbnormalSample> {
+    abnormalities
+        .iter()
+        .find(|(idx, _)| *idx == i)
+        .map(|(_, abnormality)| *abnormality)
+}
+
+/// Format sample with fixed precision, avoiding `-0` noise
+fn format_sample(sample: f32, precision: usize) -> String {
+    let formatted = format!("{sample:.precision$}");
+    if formatted.starts_with('-') && formatted[1..].chars().all(|c| c == '0' || c == '.') {
+        formatted[1..].to_string()
+    } else {
+        formatted
+    }
+}
+
+/// Dump captured samples as CSV, one row per sample
+///
+/// Sample indices count warm-up samples, same as X axis of charts.
+/// Metadata is written as leading `#` comment lines,
+/// abnormal output samples as `NaN`, `inf` or `-inf`
+pub(crate) fn encode_csv(data: &SnapshotData, config: &SampleDumpConfig) -> String {
+    let channels = dump_channels(data, config);
+    let mut csv = String::new();
+
+    if config.include_metadata {
+        writeln!(csv, "# sample_rate: {}", data.sample_rate).unwrap();
+        writeln!(csv, "# num_samples: {}", data.num_samples).unwrap();
+        writeln!(csv, "# warm_up_samples: {}", data.start_sample).unwrap();
+        writeln!(
+            csv,
+            "# processing_mode: {}",
+            processing_mode(data.processing_mode)
+        )
+        .unwrap();
+    }
+
+    csv.push_str("sample");
+    for channel in &channels {
+        csv.push(',');
+        csv.push_str(&channel.name);
+    }
+    csv.push('\n');
+
+    for i in 0..data.num_samples {
+        write!(csv, "{}", i + data.start_sample).unwrap();
+        for channel in &channels {
+            csv.push(',');
+            match abnormality_at(channel.abnormalities, i) {
+                Some(AbnormalSample::Nan) => csv.push_str("NaN"),
+                Some(AbnormalSample::NegInf) => csv.push_str("-inf"),
+                Some(AbnormalSample::PosInf) => csv.push_str("inf"),
+                // Channels shorter than `num_samples` leave empty cells
+                None => {
+                    if let Some(&sample) = channel.samples.get(i) {
+                        csv.push_str(&format_sample(sample, config.precision));
+                    }
+                }
+            }
+        }
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Dump captured samples as JSON, one sample per line
+///
+/// Abnormal output samples are written as `null` and listed in `abnormalities`,
+/// their sample indices count warm-up samples, same as X axis of charts
+pub(crate) fn encode_json(data: &SnapshotData, config: &SampleDumpConfig) -> String {
+    let channels = dump_channels(data, config);
+    let mut json = String::from("{\n");
+
+    if config.include_metadata {
+        writeln!(json, "  \"sample_rate\": {:?},", data.sample_rate).unwrap();
+        writeln!(json, "  \"num_samples\": {},", data.num_samples).unwrap();
+        writeln!(json, "  \"warm_up_samples\": {},", data.start_sample).unwrap();
+        writeln!(
+            json,
+            "  \"processing_mode\": \"{}\",",
+            processing_mode(data.processing_mode)
+        )
+        .unwrap();
+    }
+
+    json.push_str("  \"channels\": [");
+    for (c, channel) in channels.iter().enumerate() {
+        json.push_str(if c == 0 { "\n" } else { ",\n" });
+        writeln!(json, "    {{").unwrap();
+        writeln!(json, "      \"name\": \"{}\",", channel.name).unwrap();
+
+        json.push_str("      \"abnormalities\": [");
+        for (a, (i, abnormality)) in channel.abnormalities.iter().enumerate() {
+            if a > 0 {
+                json.push_str(", ");
+            }
+            write!(
+                json,
+                "{{ \"sample\": {}, \"kind\": \"{}\" }}",
+                i + data.start_sample,
+                match abnormality {
+                    AbnormalSample::Nan => "NaN",
+                    AbnormalSample::NegInf => "-inf",
+                    AbnormalSample::PosInf => "inf",
+                }
+            )
+            .unwrap();
+        }
+        json.push_str("],\n");
+
+        json.push_str("      \"samples\": [");
+        for (i, sample) in channel.samples.iter().take(data.num_samples).enumerate() {
+            json.push_str(if i == 0 { "\n" } else { ",\n" });
+            json.push_str("        ");
+            if !sample.is_finite() || abnormality_at(channel.abnormalities, i).is_some() {
+                json.push_str("null");
+            } else {
+                json.push_str(&format_sample(*sample, config.precision));
+            }
+        }
+        json.push_str("\n      ]\n    }");
+    }
+    json.push_str("\n  ]\n}\n");
+
+    json
+}
@@ -0,0 +1,150 @@
+use std::fmt::Write;
+
+use crate::config::TextChartConfig;
+use crate::data::SnapshotData;
+
+/// Characters used to draw text waveforms
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextChartStyle {
+    /// Unicode braille patterns, 2x4 dots per character
+    #[default]
+    Braille,
+    /// Unicode block elements, 1x2 cells per character
+    Blocks,
+}
+
+impl TextChartStyle {
+    /// Dot columns and rows per character
+    fn resolution(&self) -> (usize, usize) {
+        match self {
+            TextChartStyle::Braille => (2, 4),
+            TextChartStyle::Blocks => (1, 2),
+        }
+    }
+
+    /// Character for dots of one cell, `dots[column][row]`
+    fn character(&self, dots: &[&[bool]]) -> char {
+        match self {
+            TextChartStyle::Braille => {
+                // Bit of each dot in braille patterns block, by column then row
+                const BITS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+                let bits = dots
+                    .iter()
+                    .zip(BITS.iter())
+                    .flat_map(|(column, bits)| column.iter().zip(bits.iter()))
+                    .filter(|(dot, _)| **dot)
+                    .fold(0, |acc, (_, bit)| acc | bit);
+                char::from_u32(0x2800 + bits).unwrap_or(' ')
+            }
+            TextChartStyle::Blocks => match (dots[0][0], dots[0][1]) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            },
+        }
+    }
+}
+
+/// Plot one channel into `columns x rows` grid of dots using min/max decimation
+///
+/// Each column covers an equal share of samples and fills dots between
+/// their minimum and maximum, joined with the last sample of the previous column
+fn plot_dots(samples: &[f32], columns: usize, rows: usize) -> Vec<Vec<bool>> {
+    let mut dots = vec![vec![false; rows]; columns];
+    if samples.is_empty() || rows == 0 {
+        return dots;
+    }
+
+    let min_val = samples.iter().cloned().fold(f32::INFINITY, f32::min);
+    let max_val = samples.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let range = (max_val - min_val).max(f32::EPSILON);
+    let row_of = |value: f32| {
+        let position = (max_val - value) / range * (rows - 1) as f32;
+        (position.round() as usize).min(rows - 1)
+    };
+
+    let mut previous: Option<f32> = None;
+    for (column, column_dots) in dots.iter_mut().enumerate() {
+        let start = column * samples.len() / columns;
+        let end = ((column + 1) * samples.len() / columns)
+            .max(start + 1)
+            .min(samples.len());
+        let chunk = &samples[start.min(samples.len() - 1)..end];
+
+        let (low, high) = chunk.iter().chain(previous.iter()).fold(
+            (f32::INFINITY, f32::NEG_INFINITY),
+            |(low, high), &sample| (low.min(sample), high.max(sample)),
+        );
+        for dot in &mut column_dots[row_of(high)..=row_of(low)] {
+            *dot = true;
+        }
+        previous = chunk.last().cloned();
+    }
+
+    dots
+}
+
+/// Render channels as fixed-width text waveforms, one block of lines per channel
+pub(crate) fn generate_text_chart(data: &SnapshotData, config: &TextChartConfig) -> String {
+    let chart_config = &config.chart;
+    let (dots_x, dots_y) = config.style.resolution();
+    let columns = config.width.max(1) * dots_x;
+    let rows = config.height.max(1) * dots_y;
+
+    let inputs = data
+        .inputs
+        .iter()
+        .enumerate()
+        .filter(|_| chart_config.with_inputs)
+        .map(|(i, samples)| {
+            let label = chart_config
+                .input_titles
+                .get(i)
+                .cloned()
+                .unwrap_or_else(|| format!("Input Ch#{i}"));
+            (label, samples)
+        });
+    let outputs = data.outputs.iter().enumerate().map(|(i, samples)| {
+        let label = chart_config
+            .output_titles
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| format!("Output Ch#{i}"));
+        (label, samples)
+    });
+
+    let mut text = String::new();
+    if let Some(title) = &chart_config.chart_title {
+        writeln!(text, "{title}").unwrap();
+    }
+
+    for (label, samples) in inputs.chain(outputs) {
+        let samples = &samples[..data.num_samples.min(samples.len())];
+        if chart_config.show_labels {
+            let min_val = samples.iter().cloned().fold(f32::INFINITY, f32::min);
+            let max_val = samples.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+            if samples.is_empty() {
+                writeln!(text, "{label}").unwrap();
+            } else {
+                writeln!(text, "{label} [{min_val:.3}, {max_val:.3}]").unwrap();
+            }
+        }
+
+        let dots = plot_dots(samples, columns, rows);
+        for line in 0..config.height.max(1) {
+            let row_range = line * dots_y..(line + 1) * dots_y;
+            let line_text: String = dots
+                .chunks(dots_x)
+                .map(|cell| {
+                    let cell: Vec<&[bool]> = cell.iter().map(|c| &c[row_range.clone()]).collect();
+                    config.style.character(&cell)
+                })
+                .collect();
+            // Editors commonly strip trailing spaces of inline snapshots
+            writeln!(text, "{}", line_text.trim_end_matches(' ')).unwrap();
+        }
+    }
+
+    text
+}
@@ -0,0 +1,254 @@
+use plotters::backend::SVGBackend;
+use plotters::drawing::IntoDrawingArea;
+use plotters::element::DashedPathElement;
+use plotters::prelude::*;
+
+use crate::chart::{finish_svg, group_axis_color, layout_groups};
+use crate::chart_data::ChannelChartData;
+use crate::config::{SeriesStyle, TransferCurveConfig, YRange};
+use crate::data::SnapshotData;
+use crate::series::draw_waveform_series;
+use crate::util::{get_contrasting_color, parse_hex_color};
+
+/// Plot every output against the first input, one chart per group of outputs
+pub(crate) fn generate_transfer_curve_svg(
+    data: &SnapshotData,
+    config: &TransferCurveConfig,
+) -> String {
+    let chart_config = &config.chart;
+    let output_charts: Vec<ChannelChartData> = data
+        .outputs
+        .iter()
+        .enumerate()
+        .map(|(i, samples)| {
+            let abnormalities = data
+                .abnormalities
+                .get(i)
+                .map(|a| a.as_slice())
+                .unwrap_or_default();
+            ChannelChartData::from_output_data(samples, abnormalities, i, chart_config)
+        })
+        .collect();
+    let groups = layout_groups(chart_config, vec![], output_charts);
+
+    let inputs = data.inputs.first().filter(|inputs| !inputs.is_empty());
+    let (Some(inputs), false) = (inputs, groups.is_empty()) else {
+        return "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 100 100\"><text>Empty</text></svg>".to_string();
+    };
+
+    let height_per_group = chart_config.svg_height_per_channel;
+    let svg_width = chart_config.svg_width.unwrap_or(height_per_group) as u32;
+    let total_height = (height_per_group * groups.len()) as u32;
+
+    let mut svg_buffer = String::new();
+    {
+        let root =
+            SVGBackend::with_string(&mut svg_buffer, (svg_width, total_height)).into_drawing_area();
+
+        let bg_color = parse_hex_color(&chart_config.background_color);
+        root.fill(&bg_color).unwrap();
+
+        let current_area = if let Some(ref title) = chart_config.chart_title {
+            let title_color = get_contrasting_color(&bg_color);
+            let text_style = TextStyle::from(("sans-serif", 20)).color(&title_color);
+            root.titled(title, text_style).unwrap()
+        } else {
+            root
+        };
+
+        let areas = current_area.split_evenly((groups.len(), 1));
+        for (group, area) in groups.iter().zip(areas.iter()) {
+            transfer_curve_chart(group, inputs, config, area);
+        }
+
+        current_area.present().unwrap();
+    }
+
+    finish_svg(svg_buffer, chart_config, svg_width, total_height)
+}
+
+/// Slope between neighbouring points, one-sided at both ends
+fn derivative(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let last = points.len().saturating_sub(1);
+    (0..points.len())
+        .filter(|_| last > 0)
+        .map(|i| {
+            let (a, b) = (points[i.saturating_sub(1)], points[(i + 1).min(last)]);
+            let dx = b.0 - a.0;
+            let slope = if dx == 0.0 { 0.0 } else { (b.1 - a.1) / dx };
+            (points[i].0, slope)
+        })
+        .collect()
+}
+
+/// Data range padded by 10%
+fn padded_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    if min > max {
+        return (-1.0, 1.0);
+    }
+    let padding = (max - min).max(f32::EPSILON as f64) * 0.1;
+    (min - padding, max + padding)
+}
+
+fn transfer_curve_chart<DB: DrawingBackend>(
+    group: &[ChannelChartData],
+    inputs: &[f32],
+    config: &TransferCurveConfig,
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+) {
+    let chart_config = &config.chart;
+    let axis_color = group_axis_color(group);
+
+    let curves: Vec<Vec<(f64, f64)>> = group
+        .iter()
+        .map(|entry| {
+            inputs
+                .iter()
+                .zip(&entry.data)
+                .map(|(&x, &y)| (x as f64, y as f64))
+                .collect()
+        })
+        .collect();
+    let slopes: Vec<Vec<(f64, f64)>> = if config.show_derivative {
+        curves.iter().map(|curve| derivative(curve)).collect()
+    } else {
+        vec![]
+    };
+
+    let (x_min, x_max) = inputs
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &x| {
+            (min.min(x as f64), max.max(x as f64))
+        });
+    let (x_min, x_max) = (x_min, x_max.max(x_min + f32::EPSILON as f64));
+    let (y_min, y_max) = match chart_config.y_range {
+        YRange::Fixed { min, max } => (min.min(max) as f64, min.max(max) as f64),
+        YRange::Auto | YRange::Shared => padded_range(curves.iter().flatten().map(|&(_, y)| y)),
+    };
+    let (slope_min, slope_max) = padded_range(
+        slopes
+            .iter()
+            .flatten()
+            .map(|&(_, slope)| slope)
+            .chain(std::iter::once(0.0)),
+    );
+
+    let label_area = if chart_config.show_labels { 50 } else { 0 };
+    let mut chart = ChartBuilder::on(area)
+        .margin(5)
+        .x_label_area_size(if chart_config.show_labels { 35 } else { 0 })
+        .y_label_area_size(label_area)
+        .right_y_label_area_size(if config.show_derivative {
+            label_area
+        } else {
+            0
+        })
+        .build_cartesian_2d(x_min..x_max, y_min..y_max)
+        .unwrap()
+        .set_secondary_coord(x_min..x_max, slope_min..slope_max);
+
+    let y_desc = match group {
+        [single] => single.label.clone().unwrap_or_else(|| "Output".to_string()),
+        _ => "Output".to_string(),
+    };
+
+    let mut mesh = chart.configure_mesh();
+    mesh.axis_style(axis_color.mix(0.3));
+    if !chart_config.show_grid {
+        mesh.disable_mesh();
+    } else {
+        mesh.light_line_style(axis_color.mix(0.1))
+            .bold_line_style(axis_color.mix(0.2));
+    }
+    if chart_config.show_labels {
+        mesh.x_labels(chart_config.max_labels_x_axis.unwrap_or(9))
+            .y_labels(5)
+            .x_desc("Input")
+            .y_desc(y_desc)
+            .label_style(("sans-serif", 10, &axis_color));
+    }
+    mesh.draw().unwrap();
+
+    if config.show_derivative {
+        let mut axes = chart.configure_secondary_axes();
+        axes.axis_style(axis_color.mix(0.3));
+        if chart_config.show_labels {
+            axes.y_labels(5)
+                .y_desc("Slope")
+                .label_style(("sans-serif", 10, &axis_color));
+        }
+        axes.draw().unwrap();
+    }
+
+    // Identity line, where the curve passes the input through unchanged
+    let (from, to) = (x_min.max(y_min), x_max.min(y_max));
+    if config.show_identity && from < to {
+        chart
+            .draw_series(std::iter::once(PathElement::new(
+                [(from, from), (to, to)],
+                get_contrasting_color(&parse_hex_color(&chart_config.background_color)).mix(0.3),
+            )))
+            .unwrap();
+    }
+
+    let columns = chart.plotting_area().dim_in_pixel().0 as usize;
+    let mut has_legend = false;
+    for (entry, curve) in group.iter().zip(curves) {
+        let style = match chart_config.series_style.resolve(curve.len(), columns) {
+            SeriesStyle::Stems | SeriesStyle::Points => SeriesStyle::Points,
+            _ => SeriesStyle::Line,
+        };
+        let label = entry
+            .label
+            .as_deref()
+            .filter(|_| group.len() > 1 || config.show_derivative);
+        has_legend |= draw_waveform_series(
+            &mut chart,
+            curve,
+            style,
+            chart_config,
+            entry.color,
+            false,
+            label,
+        );
+    }
+
+    for (entry, slope) in group.iter().zip(slopes) {
+        let line_style = ShapeStyle {
+            color: entry.color.mix(0.6),
+            filled: false,
+            stroke_width: chart_config.line_width as u32,
+        };
+        let series = chart
+            .draw_secondary_series(std::iter::once(DashedPathElement::new(
+                slope, 4, 3, line_style,
+            )))
+            .unwrap();
+        if let Some(label) = &entry.label {
+            let color = entry.color;
+            series
+                .label(format!("{label} slope"))
+                .legend(move |(x, y)| {
+                    DashedPathElement::new(vec![(x, y), (x + 20, y)], 4, 3, color.mix(0.6))
+                });
+            has_legend = true;
+        }
+    }
+
+    if has_legend {
+        let background = parse_hex_color(&chart_config.background_color);
+        let contrasting = get_contrasting_color(&background);
+
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .border_style(contrasting)
+            .background_style(background)
+            .label_font(TextStyle::from(("sans-serif", 10)).color(&contrasting))
+            .draw()
+            .unwrap();
+    }
+}
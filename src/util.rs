@@ -102,6 +102,21 @@ pub(crate) fn time_formatter(sample: usize, sample_rate: f64) -> String {
     }
 }
 
+pub(crate) fn frequency_formatter(frequency: f64) -> String {
+    if frequency >= 1000.0 {
+        let khz = frequency / 1000.0;
+        if (khz - khz.round()).abs() < 1e-6 {
+            format!("{}k", khz.round())
+        } else {
+            format!("{khz:.1}k")
+        }
+    } else if (frequency - frequency.round()).abs() < 1e-6 {
+        format!("{}", frequency.round())
+    } else {
+        format!("{frequency:.1}")
+    }
+}
+
 pub(crate) fn num_x_labels(data_len: usize, sample_rate: f64) -> usize {
     let sr = sample_rate;
     if !sr.is_finite() || sr <= 0.0 || data_len == 0 {
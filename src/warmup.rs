@@ -1,5 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
+use crate::error::SnapshotError;
 use crate::input::InputSource;
 
 #[derive(Default, Clone)]
@@ -33,7 +34,11 @@ impl std::fmt::Debug for WarmUp {
 }
 
 impl WarmUp {
-    pub(crate) fn warm_up_samples(&self, sample_rate: f64, num_inputs: usize) -> Vec<Vec<f32>> {
+    pub(crate) fn warm_up_samples(
+        &self,
+        sample_rate: f64,
+        num_inputs: usize,
+    ) -> Result<Vec<Vec<f32>>, SnapshotError> {
         let none_input = Rc::new(RefCell::new(InputSource::None));
         let (num_samples, input) = match self {
             WarmUp::None => (0, &none_input),
@@ -42,7 +47,7 @@ impl WarmUp {
             WarmUp::SamplesWithInput { samples, input } => (*samples, input),
         };
 
-        input.borrow_mut().make_data(num_inputs, num_samples)
+        input.borrow_mut().try_make_data(num_inputs, num_samples)
     }
 
     pub(crate) fn num_samples(&self, sample_rate: f64) -> usize {
@@ -3,13 +3,14 @@ use std::io::Cursor;
 use fundsp::wave::Wave;
 
 use crate::config::WavOutput;
+use crate::error::SnapshotError;
 
 pub(crate) fn generate_wav(
     output_data: &[Vec<f32>],
     output_mode: &WavOutput,
     sample_rate: f64,
     num_samples: usize,
-) -> Vec<u8> {
+) -> Result<Vec<u8>, SnapshotError> {
     let num_channels = output_data.len();
 
     let mut buffer = Vec::<u8>::new();
@@ -26,13 +27,9 @@ pub(crate) fn generate_wav(
     }
 
     match output_mode {
-        WavOutput::Wav16 => wave_data
-            .write_wav16(&mut cursor)
-            .expect("write .wav snapshot data"),
-        WavOutput::Wav32 => wave_data
-            .write_wav32(&mut cursor)
-            .expect("write .wav snapshot data"),
+        WavOutput::Wav16 => wave_data.write_wav16(&mut cursor)?,
+        WavOutput::Wav32 => wave_data.write_wav32(&mut cursor)?,
     }
 
-    buffer
+    Ok(buffer)
 }
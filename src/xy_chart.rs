@@ -0,0 +1,152 @@
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use plotters::prelude::*;
+
+use crate::chart_data::ChannelChartData;
+use crate::config::{SeriesStyle, SvgChartConfig, YRange};
+use crate::data::SnapshotData;
+use crate::series::draw_waveform_series;
+use crate::statistics::draw_statistics_legend;
+
+/// Captured channel plotted on an axis of `Layout::XY`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartChannel {
+    /// Input channel, plotted even when `with_inputs` is `false`
+    Input(usize),
+    /// Output channel
+    Output(usize),
+}
+
+impl ChartChannel {
+    pub(crate) fn chart_data(
+        &self,
+        data: &SnapshotData,
+        config: &SvgChartConfig,
+    ) -> Option<ChannelChartData> {
+        match *self {
+            ChartChannel::Input(idx) => data
+                .inputs
+                .get(idx)
+                .map(|samples| ChannelChartData::from_input_data(samples, idx, config)),
+            ChartChannel::Output(idx) => data.outputs.get(idx).map(|samples| {
+                let abnormalities = data
+                    .abnormalities
+                    .get(idx)
+                    .map(|a| a.as_slice())
+                    .unwrap_or_default();
+                ChannelChartData::from_output_data(samples, abnormalities, idx, config)
+            }),
+        }
+    }
+}
+
+/// Plot channel `y` against channel `x`, optionally rotated by 45° to mid/side
+///
+/// Both axes share one range, so equal channels lie on the diagonal,
+/// or on the vertical mid axis when rotated
+pub(crate) fn xy_chart<DB: DrawingBackend>(
+    data: &SnapshotData,
+    config: &SvgChartConfig,
+    (x, y): (ChartChannel, ChartChannel),
+    mid_side: bool,
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+) {
+    let (Some(x_chart), Some(y_chart)) = (x.chart_data(data, config), y.chart_data(data, config))
+    else {
+        return;
+    };
+
+    let points: Vec<(f32, f32)> = x_chart
+        .data
+        .iter()
+        .zip(&y_chart.data)
+        .map(|(&x, &y)| {
+            if mid_side {
+                ((y - x) * FRAC_1_SQRT_2, (x + y) * FRAC_1_SQRT_2)
+            } else {
+                (x, y)
+            }
+        })
+        .collect();
+
+    let (min, max) = match config.y_range {
+        YRange::Fixed { min, max } => (min.min(max) as f64, min.max(max) as f64),
+        YRange::Auto | YRange::Shared => {
+            let peak = points
+                .iter()
+                .fold(0.0_f32, |peak, &(x, y)| peak.max(x.abs()).max(y.abs()))
+                .max(f32::EPSILON) as f64;
+            (-peak * 1.1, peak * 1.1)
+        }
+    };
+
+    let color = x_chart.color;
+    let (x_desc, y_desc) = match (&x_chart.label, &y_chart.label) {
+        (Some(x_label), Some(y_label)) if mid_side => (
+            Some(format!("Side ({y_label} - {x_label})")),
+            Some(format!("Mid ({x_label} + {y_label})")),
+        ),
+        (x_label, y_label) => (x_label.clone(), y_label.clone()),
+    };
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(5)
+        .x_label_area_size(if config.show_labels { 35 } else { 0 })
+        .y_label_area_size(if config.show_labels { 50 } else { 0 })
+        .build_cartesian_2d(min..max, min..max)
+        .unwrap();
+
+    let mut mesh = chart.configure_mesh();
+    mesh.axis_style(color.mix(0.3));
+    if !config.show_grid {
+        mesh.disable_mesh();
+    } else {
+        mesh.light_line_style(color.mix(0.1))
+            .bold_line_style(color.mix(0.2));
+    }
+    if config.show_labels {
+        mesh.x_labels(5)
+            .y_labels(5)
+            .label_style(("sans-serif", 10, &color));
+        if let Some(x_desc) = &x_desc {
+            mesh.x_desc(x_desc);
+        }
+        if let Some(y_desc) = &y_desc {
+            mesh.y_desc(y_desc);
+        }
+    }
+    mesh.draw().unwrap();
+
+    // Reference of equal channels
+    let reference = if mid_side {
+        [(0.0, min), (0.0, max)]
+    } else {
+        [(min, min), (max, max)]
+    };
+    chart
+        .draw_series(std::iter::once(PathElement::new(reference, color.mix(0.3))))
+        .unwrap();
+
+    let style = match config.series_style {
+        SeriesStyle::Points => SeriesStyle::Points,
+        _ => SeriesStyle::Line,
+    };
+    draw_waveform_series(
+        &mut chart,
+        points
+            .into_iter()
+            .map(|(x, y)| (x as f64, y as f64))
+            .collect(),
+        style,
+        config,
+        color,
+        false,
+        None,
+    );
+    draw_statistics_legend(
+        &[x_chart, y_chart],
+        config,
+        data.start_sample,
+        &chart.plotting_area().strip_coord_spec(),
+    );
+}
@@ -0,0 +1,110 @@
+/// Scale of the Y axis of waveform charts
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum YScale {
+    /// Sample values as they are
+    #[default]
+    Linear,
+    /// Magnitude in dBFS, `20 * log10(|sample|)`
+    ///
+    /// Samples quieter than `floor` dB, including silence, plot at `floor`
+    Decibel {
+        /// Lowest plotted level in dB, e.g. `-96.0`
+        floor: f32,
+    },
+    /// Symmetric logarithm, `sign(sample) * log10(1 + |sample| / linear_threshold)`
+    ///
+    /// Nearly linear within `±linear_threshold`, logarithmic beyond it,
+    /// so the sign of quiet samples stays visible
+    SymLog {
+        /// Magnitude where the scale turns logarithmic, e.g. `0.001`
+        linear_threshold: f32,
+    },
+}
+
+impl YScale {
+    /// Position of `sample` on the Y axis
+    pub(crate) fn apply(&self, sample: f32) -> f32 {
+        match *self {
+            YScale::Linear => sample,
+            YScale::Decibel { floor } => (20.0 * sample.abs().log10()).max(floor),
+            YScale::SymLog { linear_threshold } => {
+                let threshold = linear_threshold.max(f32::MIN_POSITIVE);
+                sample.signum() * (sample.abs() / threshold).ln_1p() / std::f32::consts::LN_10
+            }
+        }
+    }
+
+    /// Positions of `samples` on the Y axis
+    pub(crate) fn apply_all(&self, samples: &[f32]) -> Vec<f32> {
+        samples.iter().map(|&sample| self.apply(sample)).collect()
+    }
+
+    /// Lowest possible Y position, if bounded
+    pub(crate) fn floor(&self) -> Option<f64> {
+        match *self {
+            YScale::Decibel { floor } => Some(floor as f64),
+            YScale::Linear | YScale::SymLog { .. } => None,
+        }
+    }
+
+    /// Lower edge of the `±rms` band around zero
+    ///
+    /// Magnitude scales fold negative values, so the band reaches down to the floor
+    pub(crate) fn rms_lower(&self, rms: f32) -> f32 {
+        match self.floor() {
+            Some(floor) => floor as f32,
+            None => self.apply(-rms),
+        }
+    }
+
+    /// Y axis label of position `y`, `None` keeps default labels
+    pub(crate) fn label(&self, y: f64) -> Option<String> {
+        match *self {
+            YScale::Linear => None,
+            YScale::Decibel { .. } => Some(format!("{} dB", trim_number(y, 1))),
+            YScale::SymLog { linear_threshold } => {
+                let threshold = linear_threshold.max(f32::MIN_POSITIVE) as f64;
+                let value = y.signum() * threshold * (y.abs() * std::f64::consts::LN_10).exp_m1();
+                Some(if value != 0.0 && value.abs() < 0.01 {
+                    format!("{value:.1e}")
+                } else {
+                    trim_number(value, 3)
+                })
+            }
+        }
+    }
+}
+
+/// `value` with up to `decimals` decimals, without trailing zeros
+fn trim_number(value: f64, decimals: usize) -> String {
+    let text = format!("{value:.decimals$}");
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
+
+/// Range of the Y axis of waveform charts
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum YRange {
+    /// Each chart fits its own samples, padded by 10%
+    #[default]
+    Auto,
+    /// All charts of a snapshot fit samples of all plotted channels, padded by 10%
+    Shared,
+    /// Same range in every chart and snapshot, in sample values, e.g. `-1.0..1.0`
+    ///
+    /// Shows gain changes which auto ranges hide.
+    /// With `YScale::Decibel` the lower end of the axis is the floor
+    Fixed {
+        /// Bottom of the Y axis
+        min: f32,
+        /// Top of the Y axis
+        max: f32,
+    },
+}